@@ -1 +1,2 @@
 pub(crate) type DynErr = Box<dyn std::error::Error + Send + Sync>;
+pub(crate) type SentinelFormat<Sentinel> = Box<dyn Fn(&Sentinel) -> String + Send + Sync>;
@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+use crate::ProbeResult;
+
+use super::policy::{Action, ProbeHistory, SchedulePolicy};
+
+/// Polls slower and slower while there's nothing to do, from `min` up to `max`
+#[derive(Clone, Debug)]
+pub struct AdaptivePolling {
+    min: Duration,
+    max: Duration,
+    factor: f64,
+    current: Option<Duration>,
+}
+
+impl AdaptivePolling {
+    pub fn new(min: Duration, max: Duration) -> Self {
+        Self {
+            min,
+            max,
+            factor: 2.0,
+            current: None,
+        }
+    }
+
+    /// Stretch the delay by this factor each time instead of doubling it
+    ///
+    /// # Panics
+    ///
+    /// If `factor` isn't a finite number of at least `1`
+    pub fn with_factor(mut self, factor: f64) -> Self {
+        assert!(
+            factor.is_finite() && factor >= 1.0,
+            "adaptive polling factor must be finite and at least 1"
+        );
+        self.factor = factor;
+        self
+    }
+
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = match self.current {
+            // clamped before it's a duration, which would overflow way past any sensible max
+            Some(current) => {
+                let stretched = current.as_secs_f64() * self.factor;
+                if stretched < self.max.as_secs_f64() {
+                    Duration::from_secs_f64(stretched)
+                } else {
+                    self.max
+                }
+            }
+            None => self.min,
+        };
+        self.current = Some(delay);
+        delay
+    }
+
+    /// Goes back to polling every `min`
+    pub fn reset(&mut self) {
+        self.current = None;
+    }
+}
+
+impl SchedulePolicy for AdaptivePolling {
+    fn decide(&mut self, _result: &ProbeResult, _history: &ProbeHistory) -> Action {
+        Action::Sleep(self.next_delay())
+    }
+
+    fn reset(&mut self) {
+        AdaptivePolling::reset(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stretches_up_to_max_and_snaps_back() {
+        let mut polling = AdaptivePolling::new(Duration::from_secs(1), Duration::from_secs(5));
+
+        let delays = (0..5).map(|_| polling.next_delay()).collect::<Vec<_>>();
+        assert_eq!(delays, [1, 2, 4, 5, 5].map(Duration::from_secs).to_vec(),);
+
+        polling.reset();
+        assert_eq!(polling.next_delay(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn does_not_overflow_with_large_factors() {
+        let mut polling =
+            AdaptivePolling::new(Duration::from_secs(1), Duration::MAX).with_factor(f64::MAX);
+
+        polling.next_delay();
+        assert_eq!(polling.next_delay(), Duration::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "factor")]
+    fn rejects_factors_that_would_shrink_the_delay() {
+        AdaptivePolling::new(Duration::from_secs(1), Duration::from_secs(5)).with_factor(0.5);
+    }
+}
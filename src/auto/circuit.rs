@@ -0,0 +1,160 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::ProbeResult;
+
+use super::policy::{Action, ProbeHistory, SchedulePolicy};
+
+/// Stops probing for a while after too many failures in a row, so a downstream that's hard
+/// down isn't hammered with probes.
+///
+/// Clones share their failures and state, so the same breaker can handle several outcomes, e.g.
+/// with [`AutoProberCfg::with_on_error`](super::strategy::AutoProberCfg::with_on_error).
+#[derive(Clone, Debug)]
+pub struct CircuitBreaker {
+    threshold: u32,
+    cool_off: Duration,
+    delay: Duration,
+    breaker: Arc<Mutex<Breaker>>,
+}
+
+#[derive(Debug)]
+struct Breaker {
+    failures: u32,
+    state: CircuitState,
+}
+
+/// Whether a [`CircuitBreaker`] lets probes through
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Probing as usual
+    #[default]
+    Closed,
+    /// Waiting for the cool-off before trying again
+    Open,
+    /// Trying a single probe to see whether it can close again
+    HalfOpen,
+}
+
+impl CircuitBreaker {
+    /// Opens after `threshold` failures in a row, and waits `cool_off` before a trial probe
+    pub fn new(threshold: u32, cool_off: Duration) -> Self {
+        Self {
+            threshold,
+            cool_off,
+            delay: Duration::ZERO,
+            breaker: Arc::new(Mutex::new(Breaker {
+                failures: 0,
+                state: CircuitState::Closed,
+            })),
+        }
+    }
+
+    /// Wait this long between failures while still closed, instead of trying again right away
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn state(&self) -> CircuitState {
+        self.breaker.lock().unwrap().state
+    }
+
+    /// Counts a failure and returns how long to wait before the next probe
+    pub fn fail(&mut self) -> Duration {
+        let mut breaker = self.breaker.lock().unwrap();
+        breaker.failures += 1;
+        match breaker.state {
+            CircuitState::Closed if breaker.failures < self.threshold => self.delay,
+            CircuitState::Closed | CircuitState::HalfOpen => {
+                breaker.transition(CircuitState::Open);
+                self.cool_off
+            }
+            CircuitState::Open => self.cool_off,
+        }
+    }
+
+    /// Lets a single trial probe through once the cool-off is over
+    pub fn half_open(&mut self) {
+        let mut breaker = self.breaker.lock().unwrap();
+        if breaker.state == CircuitState::Open {
+            breaker.transition(CircuitState::HalfOpen);
+        }
+    }
+
+    /// Closes it after a probe went through
+    pub fn reset(&mut self) {
+        let mut breaker = self.breaker.lock().unwrap();
+        breaker.failures = 0;
+        if breaker.state != CircuitState::Closed {
+            breaker.transition(CircuitState::Closed);
+        }
+    }
+}
+
+impl Breaker {
+    fn transition(&mut self, to: CircuitState) {
+        tracing::warn!(event = "circuit-breaker", from = ?self.state, to = ?to, failures = self.failures);
+        self.state = to;
+    }
+}
+
+impl SchedulePolicy for CircuitBreaker {
+    fn decide(&mut self, _result: &ProbeResult, _history: &ProbeHistory) -> Action {
+        Action::Sleep(self.fail())
+    }
+
+    fn woke(&mut self) {
+        self.half_open();
+    }
+
+    fn reset(&mut self) {
+        CircuitBreaker::reset(self)
+    }
+
+    fn circuit(&self) -> Option<CircuitState> {
+        Some(self.state())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opens_after_threshold_and_closes_on_reset() {
+        let cool_off = Duration::from_secs(30);
+        let mut breaker = CircuitBreaker::new(2, cool_off);
+
+        assert_eq!(breaker.fail(), Duration::ZERO);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        assert_eq!(breaker.fail(), cool_off);
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        breaker.half_open();
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+
+        // a failed trial opens it right away
+        assert_eq!(breaker.fail(), cool_off);
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        breaker.half_open();
+        breaker.reset();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert_eq!(breaker.fail(), Duration::ZERO);
+    }
+
+    #[test]
+    fn clones_count_failures_together() {
+        let cool_off = Duration::from_secs(30);
+        let mut store_errors = CircuitBreaker::new(2, cool_off);
+        let mut processor_errors = store_errors.clone();
+
+        store_errors.fail();
+        assert_eq!(processor_errors.fail(), cool_off);
+        assert_eq!(store_errors.state(), CircuitState::Open);
+    }
+}
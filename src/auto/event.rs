@@ -0,0 +1,137 @@
+use std::time::Duration;
+
+use async_broadcast::{InactiveReceiver, Receiver, RecvError, Sender, TryRecvError};
+
+use crate::ProbeOutcome;
+
+use super::exit::ProbeSummary;
+
+/// How many events a subscriber can fall behind before it starts missing the oldest ones
+const CAPACITY: usize = 64;
+
+/// Something that happened in a running [`AutoProber`](super::AutoProber)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AutoProberEvent<Sentinel> {
+    /// A probe is about to run. `attempt` counts every probe since it was spawned, from 1.
+    ProbeStarted { attempt: u64 },
+    /// The probe advanced the sentinel
+    Succeeded,
+    /// The probe came out empty
+    Empty,
+    /// The probe failed, either as an [`ProbeOutcome::Error`] or a [`ProbeOutcome::PendingCommit`]
+    Errored {
+        outcome: ProbeOutcome,
+        error: String,
+    },
+    /// Someone else changed the stored sentinel while probing
+    Conflicted,
+    /// A sentinel was committed. This also happens when skipping past a failed sentinel.
+    Committed {
+        previous: Option<Sentinel>,
+        committed: Sentinel,
+        processor_time: Duration,
+        commit_time: Duration,
+    },
+    /// Waiting this long before the next probe
+    Sleeping { delay: Duration },
+    /// Nothing was committed for longer than the stall threshold. It's only sent again after it
+    /// [recovers](AutoProberEvent::Recovered).
+    Stalled { since_commit: Duration },
+    /// It committed again after stalling
+    Recovered { stalled_for: Duration },
+    /// The backoff for this outcome ran out of attempts, so it's about to stop
+    BackoffExhausted { outcome: ProbeOutcome },
+    /// The prober stopped, for whatever reason. Nothing comes after this.
+    Stopped { summary: ProbeSummary },
+}
+
+/// A subscription to the events of an [`AutoProber`](super::AutoProber).
+///
+/// Subscribers never hold the prober back: one that falls too far behind misses the oldest
+/// events instead. Cloning it subscribes again, starting from the events the original hasn't
+/// received yet.
+#[derive(Clone, Debug)]
+pub struct AutoProberEvents<Sentinel> {
+    receiver: Receiver<AutoProberEvent<Sentinel>>,
+    missed: u64,
+}
+
+impl<Sentinel: Clone> AutoProberEvents<Sentinel> {
+    /// The next event, or `None` once the prober stopped and every event was received
+    pub async fn recv(&mut self) -> Option<AutoProberEvent<Sentinel>> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(event) => return Some(event),
+                Err(RecvError::Overflowed(missed)) => self.missed += missed,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// The next event if there's one already, without waiting
+    pub fn try_recv(&mut self) -> Option<AutoProberEvent<Sentinel>> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(event) => return Some(event),
+                Err(TryRecvError::Overflowed(missed)) => self.missed += missed,
+                Err(TryRecvError::Empty | TryRecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// How many events this subscriber missed for falling behind
+    pub fn missed(&self) -> u64 {
+        self.missed
+    }
+}
+
+/// The sending side, owned by the prober
+pub(crate) struct EventSender<Sentinel> {
+    sender: Sender<AutoProberEvent<Sentinel>>,
+    /// Keeps the channel open while there are no subscribers
+    keepalive: InactiveReceiver<AutoProberEvent<Sentinel>>,
+}
+
+impl<Sentinel> EventSender<Sentinel> {
+    pub(crate) fn new() -> Self {
+        let (mut sender, receiver) = async_broadcast::broadcast(CAPACITY);
+        sender.set_overflow(true);
+        sender.set_await_active(false);
+
+        Self {
+            sender,
+            keepalive: receiver.deactivate(),
+        }
+    }
+
+    pub(crate) fn subscribe(&self) -> AutoProberEvents<Sentinel> {
+        AutoProberEvents {
+            receiver: self.sender.new_receiver(),
+            missed: 0,
+        }
+    }
+
+    /// Lets others subscribe without keeping the channel open once the prober stops
+    pub(crate) fn subscriptions(&self) -> Subscriptions<Sentinel> {
+        Subscriptions(self.keepalive.clone())
+    }
+}
+
+/// Where the [`AutoProberHandle`](super::AutoProberHandle) subscribes from
+pub(crate) struct Subscriptions<Sentinel>(InactiveReceiver<AutoProberEvent<Sentinel>>);
+
+impl<Sentinel> Subscriptions<Sentinel> {
+    pub(crate) fn subscribe(&self) -> AutoProberEvents<Sentinel> {
+        AutoProberEvents {
+            receiver: self.0.activate_cloned(),
+            missed: 0,
+        }
+    }
+}
+
+impl<Sentinel: Clone> EventSender<Sentinel> {
+    pub(crate) fn emit(&self, event: AutoProberEvent<Sentinel>) {
+        // it only fails when nobody's listening
+        let _ = self.sender.try_broadcast(event);
+    }
+}
@@ -0,0 +1,78 @@
+use crate::{ProbeError, ProbeOutcome};
+
+use super::policy::StopReason;
+
+/// What a spawned [`AutoProber`](super::AutoProber) comes out with once it stops
+#[derive(Debug)]
+pub struct AutoProberExit {
+    pub reason: ExitReason,
+    pub summary: ProbeSummary,
+}
+
+/// Why an [`AutoProber`](super::AutoProber) stopped
+#[derive(Debug)]
+pub enum ExitReason {
+    /// A probe succeeded and the strategy was to abort on success
+    Completed,
+    /// A probe came out empty and the strategy was to abort on empty
+    AbortedOnEmpty,
+    /// A probe failed and the strategy was to abort on error
+    AbortedOnError(ProbeError),
+    /// The backoff for this outcome ran out of attempts
+    RetriesExhausted {
+        outcome: ProbeOutcome,
+        /// The error that made it give up, if it was giving up on errors
+        last_error: Option<ProbeError>,
+    },
+    /// It was shut down
+    Cancelled,
+    /// Its [`SchedulePolicy`](super::policy::SchedulePolicy) stopped it for some other reason
+    Stopped(String),
+}
+
+impl ExitReason {
+    pub(crate) fn from_stop(
+        reason: StopReason,
+        outcome: ProbeOutcome,
+        last_error: Option<ProbeError>,
+    ) -> Self {
+        match (reason, last_error) {
+            (StopReason::Abort, Some(err)) => Self::AbortedOnError(err),
+            (StopReason::Abort, None) => match outcome {
+                ProbeOutcome::Success => Self::Completed,
+                ProbeOutcome::Empty => Self::AbortedOnEmpty,
+                outcome => Self::Stopped(format!("aborted on {outcome:?}")),
+            },
+            (StopReason::RetriesExhausted, last_error) => Self::RetriesExhausted {
+                outcome,
+                last_error,
+            },
+            (StopReason::Other(reason), _) => Self::Stopped(reason),
+        }
+    }
+}
+
+/// How many probes came out in each way
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProbeSummary {
+    pub probes: u64,
+    pub successes: u64,
+    pub empties: u64,
+    pub errors: u64,
+    pub pending_commits: u64,
+    pub conflicts: u64,
+}
+
+impl ProbeSummary {
+    pub(crate) fn record(&mut self, outcome: ProbeOutcome) {
+        self.probes += 1;
+        let counter = match outcome {
+            ProbeOutcome::Success => &mut self.successes,
+            ProbeOutcome::Empty => &mut self.empties,
+            ProbeOutcome::Error => &mut self.errors,
+            ProbeOutcome::PendingCommit => &mut self.pending_commits,
+            ProbeOutcome::Conflict => &mut self.conflicts,
+        };
+        *counter += 1;
+    }
+}
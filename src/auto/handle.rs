@@ -0,0 +1,216 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll},
+    time::{Duration, SystemTime},
+};
+
+use event_listener::Event;
+
+use crate::{
+    notify,
+    runtime::{Runtime, RuntimeImpl},
+    ProbeOutcome,
+};
+
+use super::{
+    circuit::CircuitState,
+    event::{AutoProberEvents, Subscriptions},
+    exit::{AutoProberExit, ProbeSummary},
+    shutdown::ShutdownToken,
+};
+
+type JoinHandle = <RuntimeImpl as Runtime>::JoinHandle<AutoProberExit>;
+
+/// Controls a spawned [`AutoProber`](super::AutoProber).
+///
+/// Awaiting it waits for the prober to finish, resolving to an error if it panicked. Dropping it
+/// leaves the prober running.
+pub struct AutoProberHandle<Sentinel> {
+    /// Only `None` while it's being dropped
+    join: Option<JoinHandle>,
+    control: Arc<Control>,
+    subscriptions: Subscriptions<Sentinel>,
+}
+
+impl<Sentinel> AutoProberHandle<Sentinel> {
+    pub(crate) fn new(
+        join: JoinHandle,
+        control: Arc<Control>,
+        subscriptions: Subscriptions<Sentinel>,
+    ) -> Self {
+        Self {
+            join: Some(join),
+            control,
+            subscriptions,
+        }
+    }
+
+    /// Stops probing after the current probe, until [`AutoProberHandle::resume`] is called
+    pub fn pause(&self) {
+        self.control.paused.store(true, Ordering::Release);
+        self.control.update_status(|status| status.paused = true);
+        self.control.wake.notify(usize::MAX);
+    }
+
+    pub fn resume(&self) {
+        self.control.paused.store(false, Ordering::Release);
+        self.control.update_status(|status| status.paused = false);
+        self.control.wake.notify(usize::MAX);
+    }
+
+    /// Probes right away, cutting short any delay or backoff. This also probes once if paused.
+    pub fn trigger(&self) {
+        self.control.triggered.store(true, Ordering::Release);
+        self.control.wake.notify(usize::MAX);
+    }
+
+    /// Stops the prober gracefully, see [`ShutdownToken`]
+    pub fn shutdown(&self) {
+        self.control.shutdown.shutdown();
+    }
+
+    pub fn status(&self) -> AutoProberStatus {
+        self.control.status.lock().unwrap().clone()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.control.finished.load(Ordering::Acquire)
+    }
+
+    /// Subscribes to what the prober does from now on. Once it stopped, there's nothing left to
+    /// receive.
+    pub fn subscribe(&self) -> AutoProberEvents<Sentinel> {
+        self.subscriptions.subscribe()
+    }
+
+    /// The runtime's own handle to the prober's task
+    pub fn into_join_handle(mut self) -> JoinHandle {
+        self.join.take().expect("only taken when dropped")
+    }
+}
+
+impl<Sentinel> Future for AutoProberHandle<Sentinel> {
+    type Output = <JoinHandle as Future>::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let join = self.join.as_mut().expect("only taken when dropped");
+        Pin::new(join).poll(cx)
+    }
+}
+
+impl<Sentinel> Drop for AutoProberHandle<Sentinel> {
+    fn drop(&mut self) {
+        if let Some(join) = self.join.take() {
+            RuntimeImpl::detach(join);
+        }
+    }
+}
+
+/// What a running [`AutoProber`](super::AutoProber) has been up to
+#[derive(Clone, Debug, Default)]
+pub struct AutoProberStatus {
+    /// How the last probe went
+    pub last_outcome: Option<ProbeOutcome>,
+    /// When a sentinel was last committed
+    pub last_commit_at: Option<SystemTime>,
+    /// How many probes were run, and how they came out
+    pub summary: ProbeSummary,
+    pub paused: bool,
+    /// The state of the circuit breaker, if the prober uses one
+    pub circuit: Option<CircuitState>,
+    /// Whether it went past its stall threshold without committing anything
+    pub stalled: bool,
+}
+
+/// State shared between a running prober and its handle
+pub(crate) struct Control {
+    paused: AtomicBool,
+    triggered: AtomicBool,
+    finished: AtomicBool,
+    /// Notified whenever any of the above changes
+    wake: Event,
+    status: Mutex<AutoProberStatus>,
+    pub(crate) shutdown: ShutdownToken,
+}
+
+impl Control {
+    pub(crate) fn new(shutdown: ShutdownToken) -> Self {
+        Self {
+            paused: AtomicBool::new(false),
+            triggered: AtomicBool::new(false),
+            finished: AtomicBool::new(false),
+            wake: Event::new(),
+            status: Mutex::new(AutoProberStatus::default()),
+            shutdown,
+        }
+    }
+
+    /// `committed` is whether the probe committed a sentinel, which also happens when skipping
+    /// one after an error
+    pub(crate) fn record(&self, outcome: ProbeOutcome, committed: bool) {
+        self.update_status(|status| {
+            status.summary.record(outcome);
+            status.last_outcome = Some(outcome);
+            if committed {
+                status.last_commit_at = Some(SystemTime::now());
+            }
+        });
+    }
+
+    pub(crate) fn set_circuit(&self, circuit: Option<CircuitState>) {
+        if circuit.is_some() {
+            self.update_status(|status| status.circuit = circuit);
+        }
+    }
+
+    pub(crate) fn set_stalled(&self, stalled: bool) {
+        self.update_status(|status| status.stalled = stalled);
+    }
+
+    pub(crate) fn finish(&self) {
+        self.finished.store(true, Ordering::Release);
+    }
+
+    pub(crate) fn summary(&self) -> ProbeSummary {
+        self.status.lock().unwrap().summary.clone()
+    }
+
+    fn update_status(&self, update: impl FnOnce(&mut AutoProberStatus)) {
+        update(&mut self.status.lock().unwrap());
+    }
+
+    /// Waits until it's time to probe, that is, when not paused or when triggered. Returns
+    /// `false` if it's shutting down instead.
+    pub(crate) async fn ready(&self) -> bool {
+        let can_probe = self.wait_until(|control| {
+            !control.paused.load(Ordering::Acquire) || control.triggered.load(Ordering::Acquire)
+        });
+        futures_lite::future::or(can_probe, self.shutdown.wait()).await;
+
+        if self.shutdown.is_shutdown() {
+            return false;
+        }
+
+        self.triggered.store(false, Ordering::Release);
+        true
+    }
+
+    /// Sleeps for `duration`, waking up early when triggered or shutting down
+    pub(crate) async fn sleep(&self, duration: Duration) {
+        let triggered = self.wait_until(|control| control.triggered.load(Ordering::Acquire));
+        futures_lite::future::or(
+            futures_lite::future::or(RuntimeImpl::sleep(duration), triggered),
+            self.shutdown.wait(),
+        )
+        .await
+    }
+
+    async fn wait_until(&self, condition: impl Fn(&Self) -> bool) {
+        notify::wait_for(&self.wake, || condition(self)).await
+    }
+}
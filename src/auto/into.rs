@@ -1,10 +1,32 @@
+use std::time::Duration;
+
 #[mockall_double::double]
 use crate::Prober;
 
-use super::{strategy::AutoProberCfg, AutoProber};
+use super::{
+    event::EventSender, policy::SchedulePolicy, shutdown::ShutdownToken, strategy::AutoProberCfg,
+    AutoProber,
+};
 
 impl<Store, Sentinel, Processor> Prober<Store, Sentinel, Processor> {
     pub fn into_auto(self, cfg: AutoProberCfg) -> AutoProber<Store, Sentinel, Processor> {
-        AutoProber { prober: self, cfg }
+        self.into_auto_with(cfg)
+    }
+
+    /// Like [`Prober::into_auto`], but scheduled by any [`SchedulePolicy`]
+    pub fn into_auto_with(
+        self,
+        policy: impl SchedulePolicy + 'static,
+    ) -> AutoProber<Store, Sentinel, Processor> {
+        AutoProber {
+            prober: self,
+            policy: Box::new(policy),
+            lease: None,
+            shutdown: ShutdownToken::new(),
+            retry_hint_bounds: (Duration::ZERO, Duration::MAX),
+            events: EventSender::new(),
+            metrics: None,
+            stall_after: None,
+        }
     }
 }
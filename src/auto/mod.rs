@@ -1,11 +1,30 @@
+pub mod adaptive;
+pub mod circuit;
+pub mod event;
+pub mod exit;
+pub mod handle;
 pub mod into;
+pub mod policy;
+pub mod shutdown;
+mod stall;
 pub mod strategy;
 
-use strategy::{AutoProberCfg, AutoProberStrategy};
+use std::{sync::Arc, time::Duration};
+
+use tracing::Instrument as _;
+
+use event::{AutoProberEvent, AutoProberEvents, EventSender};
+use exit::{AutoProberExit, ExitReason};
+use handle::{AutoProberHandle, Control};
+use policy::{Action, ProbeHistory, SchedulePolicy, StopReason};
+use shutdown::ShutdownToken;
+use stall::StallWatch;
 
 #[mockall_double::double]
 use crate::Prober;
 use crate::{
+    lease::{Lease, LeaseKeeper},
+    metrics::ProberMetrics,
     proc::Processor,
     runtime::{Runtime as _, RuntimeImpl},
     store::SentinelStore,
@@ -14,102 +33,239 @@ use crate::{
 
 pub struct AutoProber<Store, Sentinel, Proc> {
     prober: Prober<Store, Sentinel, Proc>,
-    cfg: AutoProberCfg,
+    policy: Box<dyn SchedulePolicy>,
+    lease: Option<(Box<dyn Lease + Send>, Duration)>,
+    shutdown: ShutdownToken,
+    /// How short and how long the processor's retry hints can be
+    retry_hint_bounds: (Duration, Duration),
+    events: EventSender<Sentinel>,
+    metrics: Option<ProberMetrics>,
+    stall_after: Option<Duration>,
+}
+
+impl<Store, Sentinel, Proc> AutoProber<Store, Sentinel, Proc> {
+    /// Only probe while holding `lease`, which is renewed every `renew_every` while the prober
+    /// is running. This must be shorter than the lease's expiry.
+    ///
+    /// A probe that's already running when the lease is lost still finishes, so pair this with
+    /// conditional commits to make sure it doesn't overwrite the new holder's progress.
+    pub fn with_lease(mut self, lease: impl Lease + Send + 'static, renew_every: Duration) -> Self {
+        self.lease = Some((Box::new(lease), renew_every));
+        self
+    }
+
+    /// Stop when `shutdown` is triggered, so several probers can be stopped together
+    pub fn with_shutdown(mut self, shutdown: ShutdownToken) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Keep the delays suggested by the processor between `min` and `max`. They're followed
+    /// unbounded otherwise.
+    ///
+    /// # Panics
+    ///
+    /// If `min` is greater than `max`
+    pub fn with_retry_hint_bounds(mut self, min: Duration, max: Duration) -> Self {
+        assert!(min <= max, "retry hint bounds are the wrong way around");
+        self.retry_hint_bounds = (min, max);
+        self
+    }
+
+    /// Keep track of how probing goes in `metrics`
+    pub fn with_metrics(mut self, metrics: ProberMetrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Tell when nothing was committed for longer than `threshold`, through a warning and an
+    /// [`AutoProberEvent::Stalled`]. This only happens once, until it commits again and tells
+    /// that it [recovered](AutoProberEvent::Recovered).
+    pub fn with_stall_threshold(mut self, threshold: Duration) -> Self {
+        self.stall_after = Some(threshold);
+        self
+    }
+
+    /// A token that stops this prober gracefully once it's spawned
+    pub fn shutdown_token(&self) -> ShutdownToken {
+        self.shutdown.clone()
+    }
+
+    /// Subscribes to what the prober does once it's spawned
+    pub fn subscribe(&self) -> AutoProberEvents<Sentinel> {
+        self.events.subscribe()
+    }
+
+    fn set_backoff(&self, delay: Duration) {
+        if let Some(metrics) = &self.metrics {
+            metrics.set_backoff(delay);
+        }
+    }
 }
 
 impl<Store, Sentinel, Proc> AutoProber<Store, Sentinel, Proc>
 where
     Store: SentinelStore<Sentinel> + Send + 'static,
     Proc: Processor<Sentinel = Sentinel> + Send + 'static,
-    Sentinel: Send + 'static,
+    Sentinel: Clone + Send + Sync + 'static,
 {
-    pub fn spawn(mut self) -> tokio::task::JoinHandle<()> {
-        RuntimeImpl::spawn(async move {
-            loop {
-                match self.prober.probe().await {
-                    ProbeResult::Success => match self.cfg.on_success {
-                        AutoProberStrategy::Abort => return,
-                        AutoProberStrategy::DelaySecs(secs) => {
-                            RuntimeImpl::sleep(secs.into()).await;
-                        }
-                        AutoProberStrategy::Continue => {}
-                        AutoProberStrategy::Backoff(ref mut backoff) => {
-                            match backoff.next_sleep() {
-                                Some(delay) => RuntimeImpl::sleep(delay.as_secs()).await,
-                                None => return, // maybe an err would be better
-                            }
-                        }
-                    },
-                    ProbeResult::Empty => match self.cfg.on_empty {
-                        AutoProberStrategy::Abort => {
-                            tracing::info!(event = "probe-empty", "abort");
-                            return;
-                        }
-                        AutoProberStrategy::DelaySecs(secs) => {
-                            tracing::info!(event = "probe-empty", "retrying in {secs} seconds");
-                            RuntimeImpl::sleep(secs.into()).await;
+    pub fn spawn(mut self) -> AutoProberHandle<Sentinel> {
+        let control = Arc::new(Control::new(self.shutdown.clone()));
+        let subscriptions = self.events.subscriptions();
+
+        let span = self.prober.span();
+        let join = RuntimeImpl::spawn({
+            let control = Arc::clone(&control);
+            async move {
+                // dropping it when this task ends releases the lease
+                let lease_keeper = self
+                    .lease
+                    .take()
+                    .map(|(lease, renew_every)| LeaseKeeper::spawn(lease, renew_every));
+
+                let mut history = ProbeHistory::default();
+                let mut attempt = 0;
+                let mut stall = self.stall_after.map(StallWatch::new);
+
+                let reason = loop {
+                    if !control.ready().await {
+                        tracing::info!(event = "shutdown", "stopping");
+                        break ExitReason::Cancelled;
+                    }
+
+                    if let Some(lease_keeper) = lease_keeper.as_ref().filter(|it| !it.is_held()) {
+                        futures_lite::future::or(
+                            lease_keeper.until_held(),
+                            control.shutdown.wait(),
+                        )
+                        .await;
+                        continue;
+                    }
+
+                    attempt += 1;
+                    self.events.emit(AutoProberEvent::ProbeStarted { attempt });
+                    let report = self.prober.probe_report().await;
+                    if let Some(metrics) = &self.metrics {
+                        metrics.record(&report);
+                    }
+                    // skipping past a sentinel also moves it forward
+                    let advanced =
+                        matches!(report.result, ProbeResult::Success) || report.committed.is_some();
+                    let result = report.result;
+                    control.record(result.outcome(), advanced);
+
+                    let outcome = result.outcome();
+                    history.record(outcome);
+
+                    if let Some(committed) = report.committed {
+                        self.events.emit(AutoProberEvent::Committed {
+                            previous: report.previous,
+                            committed,
+                            processor_time: report.processor_time,
+                            commit_time: report.commit_time,
+                        });
+                    }
+                    match &result {
+                        ProbeResult::Error(err) | ProbeResult::PendingCommit(err) => {
+                            tracing::error!(event = "probe-error", err = ?err);
+                            self.events.emit(AutoProberEvent::Errored {
+                                outcome,
+                                error: err.to_string(),
+                            });
                         }
-                        AutoProberStrategy::Continue => {}
-                        AutoProberStrategy::Backoff(ref mut backoff) => {
-                            match backoff.next_sleep() {
-                                Some(delay) => {
-                                    let delay_secs = delay.as_secs();
-                                    tracing::error!(
-                                        event = "probe-empty",
-                                        "trying again in {delay_secs} seconds"
-                                    );
-                                    RuntimeImpl::sleep(delay_secs).await
-                                }
-                                None => {
-                                    tracing::error!(
-                                        event = "probe-empty",
-                                        "retries exhausted, aborting"
-                                    );
-                                    // MAYBE an err would be better
-                                    return;
-                                }
-                            }
+                        ProbeResult::Conflict => {
+                            tracing::warn!(
+                                event = "probe-conflict",
+                                "sentinel was changed by someone else"
+                            );
+                            self.events.emit(AutoProberEvent::Conflicted);
                         }
-                    },
-                    ProbeResult::Error(err) => match self.cfg.on_error {
-                        AutoProberStrategy::Abort => {
-                            tracing::error!(event = "probe-error", err = ?err, "abort");
-                            err.panic();
+                        ProbeResult::Success => self.events.emit(AutoProberEvent::Succeeded),
+                        ProbeResult::Empty => self.events.emit(AutoProberEvent::Empty),
+                    }
+                    if let Some(change) = stall.as_mut().and_then(|stall| {
+                        if advanced {
+                            stall.committed()
+                        } else {
+                            stall.check()
                         }
-                        AutoProberStrategy::DelaySecs(secs) => {
-                            tracing::error!(event = "probe-error", err = ?err, "retrying in {secs} seconds");
-                            RuntimeImpl::sleep(secs.into()).await;
+                    }) {
+                        stall::report(&control, &self.events, change);
+                    }
+
+                    let action = match (
+                        self.policy.decide(&result, &history),
+                        self.prober.retry_hint(),
+                    ) {
+                        // the processor knows better when there'll be something new, but only
+                        // when there's nothing now
+                        (Action::Sleep(_) | Action::Continue, Some(hint))
+                            if matches!(result, ProbeResult::Empty) =>
+                        {
+                            let (min, max) = self.retry_hint_bounds;
+                            Action::Sleep(hint.clamp(min, max))
                         }
-                        AutoProberStrategy::Continue => {
-                            tracing::error!(event = "probe-error", err = ?err, "trying again");
+                        (action, _) => action,
+                    };
+                    control.set_circuit(self.policy.circuit());
+                    match action {
+                        Action::Continue => {}
+                        Action::Sleep(delay) => {
+                            self.set_backoff(delay);
+                            tracing::info!(
+                                event = "probe-sleep",
+                                ?outcome,
+                                "trying again in {delay:?}"
+                            );
+                            self.events.emit(AutoProberEvent::Sleeping { delay });
+                            stall::sleep(&control, stall.as_mut(), &self.events, delay).await;
+                            self.set_backoff(Duration::ZERO);
+                            self.policy.woke();
+                            control.set_circuit(self.policy.circuit());
                         }
-                        AutoProberStrategy::Backoff(ref mut backoff) => {
-                            match backoff.next_sleep() {
-                                Some(delay) => {
-                                    let delay_secs = delay.as_secs();
-                                    tracing::error!(event = "probe-error", err = ?err, "trying again in {delay_secs} seconds");
-                                    RuntimeImpl::sleep(delay_secs).await
-                                }
-                                None => {
-                                    tracing::error!(event = "probe-error", err = ?err, "retries exhausted, aborting");
-                                    // MAYBE an err would be better
-                                    return;
-                                }
+                        Action::Stop(reason) => {
+                            tracing::info!(event = "probe-stop", ?outcome, ?reason, "stopping");
+                            if reason == StopReason::RetriesExhausted {
+                                self.events
+                                    .emit(AutoProberEvent::BackoffExhausted { outcome });
                             }
+                            let last_error = match result {
+                                ProbeResult::Error(err) | ProbeResult::PendingCommit(err) => {
+                                    Some(err)
+                                }
+                                _ => None,
+                            };
+                            break ExitReason::from_stop(reason, outcome, last_error);
                         }
-                    },
-                }
+                    }
+                };
+
+                control.finish();
+                let summary = control.summary();
+                self.events.emit(AutoProberEvent::Stopped {
+                    summary: summary.clone(),
+                });
+
+                AutoProberExit { reason, summary }
             }
-        })
+            .instrument(span)
+        });
+
+        AutoProberHandle::new(join, control, subscriptions)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::auto::adaptive::AdaptivePolling;
+    use crate::auto::circuit::{CircuitBreaker, CircuitState};
+    use crate::auto::exit::ProbeSummary;
+    use crate::auto::strategy::{AutoProberCfg, AutoProberStrategy, BackoffStrategy, Jitter};
+    use crate::proc::error::ClassifiedError;
     use crate::proc::MockProcessor;
     use crate::store::MockSentinelStore;
-    use crate::MockProber;
+    use crate::{MockProber, ProbeError, ProbeOutcome};
 
     #[tokio::test]
     async fn probes_and_aborts_on_first_success() {
@@ -119,15 +275,577 @@ mod tests {
             .times(1..)
             .returning(|| ProbeResult::Success);
 
-        let auto = AutoProber {
-            prober,
-            cfg: AutoProberCfg {
-                on_success: AutoProberStrategy::Abort,
+        let auto = prober.into_auto(AutoProberCfg {
+            on_success: AutoProberStrategy::Abort,
+            ..Default::default()
+        });
+
+        let exit = auto.spawn().await.unwrap();
+        assert!(matches!(exit.reason, ExitReason::Completed));
+        assert_eq!(exit.summary.probes, 1);
+        assert_eq!(exit.summary.successes, 1);
+    }
+
+    #[tokio::test]
+    async fn finishes_probe_and_stops_on_shutdown() {
+        let shutdown = ShutdownToken::new();
+        let mut prober = MockProber::<MockSentinelStore<_>, (), MockProcessor>::new();
+        prober.expect_probe().times(1).returning({
+            let shutdown = shutdown.clone();
+            move || {
+                shutdown.shutdown();
+                ProbeResult::Empty
+            }
+        });
+
+        let auto = prober
+            .into_auto(AutoProberCfg {
+                on_empty: AutoProberStrategy::Delay(Duration::from_secs(60)),
+                ..Default::default()
+            })
+            .with_shutdown(shutdown);
+
+        let exit = tokio::time::timeout(tokio::time::Duration::from_secs(1), auto.spawn())
+            .await
+            .expect("should stop without waiting for the delay")
+            .unwrap();
+        assert!(matches!(exit.reason, ExitReason::Cancelled));
+    }
+
+    #[tokio::test]
+    async fn trigger_skips_delay() {
+        let mut prober = MockProber::<MockSentinelStore<_>, (), MockProcessor>::new();
+        prober
+            .expect_probe()
+            .times(2)
+            .returning(|| ProbeResult::Success);
+
+        let handle = prober
+            .into_auto(AutoProberCfg {
+                on_success: AutoProberStrategy::Delay(Duration::from_secs(60)),
                 ..Default::default()
-            },
+            })
+            .spawn();
+
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        let status = handle.status();
+        assert_eq!(status.summary.probes, 1);
+        assert_eq!(status.last_outcome, Some(ProbeOutcome::Success));
+        assert!(status.last_commit_at.is_some());
+
+        handle.trigger();
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        assert_eq!(handle.status().summary.probes, 2);
+
+        handle.shutdown();
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn does_not_probe_while_paused() {
+        let mut prober = MockProber::<MockSentinelStore<_>, (), MockProcessor>::new();
+        prober
+            .expect_probe()
+            .times(2)
+            .returning(|| ProbeResult::Empty);
+
+        // some runtimes start probing right away on another thread, so pause it while it waits
+        // after the first probe
+        let handle = prober
+            .into_auto(AutoProberCfg {
+                on_empty: AutoProberStrategy::Delay(Duration::from_millis(100)),
+                ..Default::default()
+            })
+            .spawn();
+        tokio::time::sleep(tokio::time::Duration::from_millis(30)).await;
+        handle.pause();
+
+        tokio::time::sleep(tokio::time::Duration::from_millis(250)).await;
+        assert_eq!(handle.status().summary.probes, 1);
+        assert!(handle.status().paused);
+
+        handle.resume();
+        tokio::time::sleep(tokio::time::Duration::from_millis(40)).await;
+        assert_eq!(handle.status().summary.probes, 2);
+
+        handle.shutdown();
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn exits_with_last_error_when_retries_run_out() {
+        let mut prober = MockProber::<MockSentinelStore<_>, (), MockProcessor>::new();
+        prober
+            .expect_probe()
+            .times(2)
+            .returning(|| ProbeResult::Error(ProbeError::Processor("upstream is down".into())));
+
+        let exit = prober
+            .into_auto(AutoProberCfg {
+                on_processor_error: AutoProberStrategy::Backoff(BackoffStrategy::new(
+                    2,
+                    Duration::from_millis(1),
+                )),
+                ..Default::default()
+            })
+            .spawn()
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            exit.reason,
+            ExitReason::RetriesExhausted {
+                outcome: ProbeOutcome::Error,
+                last_error: Some(ProbeError::Processor(_)),
+            }
+        ));
+        assert_eq!(exit.summary.errors, 2);
+    }
+
+    #[tokio::test]
+    async fn resets_backoff_when_outcome_changes() {
+        let mut prober = MockProber::<MockSentinelStore<_>, (), MockProcessor>::new();
+        let mut results = vec![
+            ProbeResult::Error(ProbeError::Processor("upstream is down".into())),
+            ProbeResult::Success,
+            ProbeResult::Error(ProbeError::Processor("upstream is down".into())),
+            ProbeResult::Success,
+            ProbeResult::Error(ProbeError::Processor("upstream is down".into())),
+            ProbeResult::Empty,
+        ]
+        .into_iter();
+        prober
+            .expect_probe()
+            .times(6)
+            .returning(move || results.next().unwrap());
+
+        let exit = prober
+            .into_auto(AutoProberCfg {
+                on_processor_error: AutoProberStrategy::Backoff(BackoffStrategy::new(
+                    2,
+                    Duration::from_millis(1),
+                )),
+                ..Default::default()
+            })
+            .spawn()
+            .await
+            .unwrap();
+
+        assert!(matches!(exit.reason, ExitReason::AbortedOnEmpty));
+        assert_eq!(exit.summary.errors, 3);
+    }
+
+    #[tokio::test]
+    async fn circuit_breaker_opens_and_closes() {
+        let mut prober = MockProber::<MockSentinelStore<_>, (), MockProcessor>::new();
+        let mut results = vec![
+            ProbeResult::Error(ProbeError::Processor("upstream is down".into())),
+            ProbeResult::Error(ProbeError::Processor("upstream is down".into())),
+            ProbeResult::Error(ProbeError::Processor("still down".into())),
+            ProbeResult::Success,
+        ]
+        .into_iter();
+        prober
+            .expect_probe()
+            .times(4)
+            .returning(move || results.next().unwrap());
+
+        // the cool-off is cut short by triggering, so it never waits on the clock
+        let cool_off = Duration::from_secs(3600);
+        let auto = prober.into_auto(AutoProberCfg {
+            on_success: AutoProberStrategy::Abort,
+            on_processor_error: AutoProberStrategy::CircuitBreaker(CircuitBreaker::new(
+                2, cool_off,
+            )),
+            ..Default::default()
+        });
+        let mut events = auto.subscribe();
+        let handle = auto.spawn();
+
+        let mut opened_after = Vec::new();
+        while let Some(event) = events.recv().await {
+            if event == (AutoProberEvent::Sleeping { delay: cool_off }) {
+                assert_eq!(handle.status().circuit, Some(CircuitState::Open));
+                opened_after.push(handle.status().summary.errors);
+                handle.trigger();
+            }
+        }
+
+        // and once more after the trial probe failed
+        assert_eq!(opened_after, vec![2, 3]);
+        assert_eq!(handle.status().circuit, Some(CircuitState::Closed));
+        assert_eq!(handle.status().summary.successes, 1);
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn adaptive_polling_speeds_up_on_success() {
+        let mut prober = MockProber::<MockSentinelStore<_>, (), MockProcessor>::new();
+        let mut results = vec![
+            ProbeResult::Empty,
+            ProbeResult::Empty,
+            ProbeResult::Success,
+            ProbeResult::Success,
+            ProbeResult::Empty,
+        ]
+        .into_iter();
+        prober
+            .expect_probe()
+            .times(5..)
+            .returning(move || results.next().unwrap_or(ProbeResult::Empty));
+
+        let auto = prober.into_auto(AutoProberCfg::adaptive(AdaptivePolling::new(
+            Duration::from_millis(1),
+            Duration::from_secs(60),
+        )));
+        let mut events = auto.subscribe();
+        let handle = auto.spawn();
+
+        let mut received = Vec::new();
+        while let Some(event) = events.recv().await {
+            let slept_again = received.contains(&AutoProberEvent::Succeeded)
+                && matches!(event, AutoProberEvent::Sleeping { .. });
+            if !matches!(event, AutoProberEvent::ProbeStarted { .. }) {
+                received.push(event);
+            }
+            if slept_again {
+                break;
+            }
+        }
+        handle.shutdown();
+
+        // the delay stretches while empty, and the successes go straight through
+        let sleeping = |millis| AutoProberEvent::Sleeping {
+            delay: Duration::from_millis(millis),
         };
+        assert_eq!(
+            received,
+            vec![
+                AutoProberEvent::Empty,
+                sleeping(1),
+                AutoProberEvent::Empty,
+                sleeping(2),
+                AutoProberEvent::Succeeded,
+                AutoProberEvent::Succeeded,
+                AutoProberEvent::Empty,
+                sleeping(1),
+            ]
+        );
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn custom_policy_sees_history() {
+        /// Aborts after 3 empties in a row
+        struct ThreeStrikes;
+
+        impl SchedulePolicy for ThreeStrikes {
+            fn decide(&mut self, _result: &ProbeResult, history: &ProbeHistory) -> Action {
+                if history.last() == Some(ProbeOutcome::Empty) && history.streak() == 3 {
+                    Action::Stop(StopReason::Other("three strikes".to_string()))
+                } else {
+                    Action::Continue
+                }
+            }
+        }
+
+        let mut prober = MockProber::<MockSentinelStore<_>, (), MockProcessor>::new();
+        let mut results = vec![
+            ProbeResult::Empty,
+            ProbeResult::Empty,
+            ProbeResult::Success,
+            ProbeResult::Empty,
+            ProbeResult::Empty,
+            ProbeResult::Empty,
+        ]
+        .into_iter();
+        prober
+            .expect_probe()
+            .times(6)
+            .returning(move || results.next().unwrap());
+
+        let exit = prober.into_auto_with(ThreeStrikes).spawn().await.unwrap();
+
+        assert!(matches!(exit.reason, ExitReason::Stopped(reason) if reason == "three strikes"));
+        assert_eq!(exit.summary.probes, 6);
+    }
+
+    #[tokio::test]
+    async fn handles_each_error_class_on_its_own() {
+        let mut prober = MockProber::<MockSentinelStore<_>, (), MockProcessor>::new();
+        let mut results = vec![
+            ProbeResult::Error(ProbeError::Processor(
+                ClassifiedError::rate_limited(Duration::from_millis(50), "429").into(),
+            )),
+            ProbeResult::Error(ProbeError::Processor(
+                ClassifiedError::permanent("bad json").into(),
+            )),
+        ]
+        .into_iter();
+        prober
+            .expect_probe()
+            .times(2)
+            .returning(move || results.next().unwrap());
+
+        let started = std::time::Instant::now();
+        let exit = prober
+            .into_auto(AutoProberCfg {
+                on_processor_error: AutoProberStrategy::Delay(Duration::from_secs(60)),
+                jitter: Jitter::Full,
+                ..Default::default()
+            })
+            .spawn()
+            .await
+            .unwrap();
+
+        assert!(matches!(exit.reason, ExitReason::AbortedOnError(_)));
+        assert!(started.elapsed() >= Duration::from_millis(50));
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn store_and_processor_errors_back_off_independently() {
+        let mut prober = MockProber::<MockSentinelStore<_>, (), MockProcessor>::new();
+        let mut results = vec![
+            ProbeResult::Error(ProbeError::Processor("upstream is down".into())),
+            ProbeResult::Error(ProbeError::Store("disk full".into())),
+            ProbeResult::Error(ProbeError::Processor("upstream is down".into())),
+        ]
+        .into_iter();
+        prober
+            .expect_probe()
+            .times(3)
+            .returning(move || results.next().unwrap());
+
+        let exit = prober
+            .into_auto(
+                AutoProberCfg::default().with_on_error(AutoProberStrategy::Backoff(
+                    BackoffStrategy::new(2, Duration::from_millis(1)),
+                )),
+            )
+            .spawn()
+            .await
+            .unwrap();
 
+        // the store error in between didn't give the processor a fresh backoff
+        assert!(matches!(
+            exit.reason,
+            ExitReason::RetriesExhausted {
+                last_error: Some(ProbeError::Processor(_)),
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn emits_events_until_it_stops() {
+        // ARRANGE
+        let mut prober = MockProber::<MockSentinelStore<_>, (), MockProcessor>::new();
+        prober
+            .expect_probe()
+            .times(2)
+            .returning(|| ProbeResult::Error(ProbeError::Processor("upstream is down".into())));
+        let auto = prober.into_auto(AutoProberCfg {
+            on_processor_error: AutoProberStrategy::Backoff(BackoffStrategy::new(
+                2,
+                Duration::from_millis(1),
+            )),
+            ..Default::default()
+        });
+        let mut events = auto.subscribe();
+
+        // ACT
+        auto.spawn().await.unwrap();
+
+        // ASSERT
+        let mut received = Vec::new();
+        while let Some(event) = events.recv().await {
+            received.push(event);
+        }
+        // backoff delays are jittered, so only check it slept
+        assert!(matches!(received[2], AutoProberEvent::Sleeping { .. }));
+        received.remove(2);
+        let errored = AutoProberEvent::Errored {
+            outcome: ProbeOutcome::Error,
+            error: "processor error: upstream is down".to_string(),
+        };
+        assert_eq!(
+            received,
+            vec![
+                AutoProberEvent::ProbeStarted { attempt: 1 },
+                errored.clone(),
+                AutoProberEvent::ProbeStarted { attempt: 2 },
+                errored,
+                AutoProberEvent::BackoffExhausted {
+                    outcome: ProbeOutcome::Error
+                },
+                AutoProberEvent::Stopped {
+                    summary: ProbeSummary {
+                        probes: 2,
+                        errors: 2,
+                        ..Default::default()
+                    }
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn subscribers_that_fall_behind_do_not_block_it() {
+        // ARRANGE
+        let shutdown = ShutdownToken::new();
+        let mut probes = 0;
+        let mut prober = MockProber::<MockSentinelStore<_>, (), MockProcessor>::new();
+        prober.expect_probe().times(40).returning({
+            let shutdown = shutdown.clone();
+            move || {
+                probes += 1;
+                if probes == 40 {
+                    shutdown.shutdown();
+                }
+                ProbeResult::Success
+            }
+        });
+        let auto = prober
+            .into_auto(AutoProberCfg {
+                on_success: AutoProberStrategy::Continue,
+                ..Default::default()
+            })
+            .with_shutdown(shutdown);
+        let mut first = auto.subscribe();
+        let mut second = first.clone();
+
+        // ACT
+        tokio::time::timeout(tokio::time::Duration::from_secs(1), auto.spawn())
+            .await
+            .expect("should not wait for anyone to read the events")
+            .unwrap();
+
+        // ASSERT
+        for events in [&mut first, &mut second] {
+            let mut received = Vec::new();
+            while let Some(event) = events.recv().await {
+                received.push(event);
+            }
+            // 40 started, 40 succeeded and the stop
+            assert_eq!(received.len() as u64 + events.missed(), 81);
+            assert!(events.missed() > 0);
+            assert!(matches!(
+                received.last(),
+                Some(AutoProberEvent::Stopped { .. })
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn subscribes_through_the_handle() {
+        // ARRANGE
+        let mut prober = MockProber::<MockSentinelStore<_>, (), MockProcessor>::new();
+        prober.expect_probe().returning(|| ProbeResult::Empty);
+        let handle = prober
+            .into_auto(AutoProberCfg {
+                on_empty: AutoProberStrategy::Delay(Duration::from_millis(1)),
+                ..Default::default()
+            })
+            .spawn();
+
+        // ACT
+        let mut events = handle.subscribe();
+        let mut alerting = handle.subscribe();
+        handle.shutdown();
+        let mut received = Vec::new();
+        while let Some(event) = events.recv().await {
+            received.push(event);
+        }
+        let late = handle.subscribe().recv().await;
+
+        // ASSERT
+        assert!(matches!(
+            received.last(),
+            Some(AutoProberEvent::Stopped { .. })
+        ));
+        assert_eq!(alerting.recv().await, received.first().cloned());
+        assert_eq!(late, None);
+    }
+
+    #[tokio::test]
+    async fn shows_the_backoff_only_while_sleeping() {
+        // ARRANGE
+        let mut probes = 0;
+        let mut prober = MockProber::<MockSentinelStore<_>, (), MockProcessor>::new();
+        prober.expect_probe().times(2).returning(move || {
+            probes += 1;
+            match probes {
+                1 => ProbeResult::Empty,
+                _ => ProbeResult::Success,
+            }
+        });
+        let metrics = ProberMetrics::named("test");
+
+        // ACT
+        let handle = prober
+            .into_auto(AutoProberCfg {
+                on_success: AutoProberStrategy::Abort,
+                on_empty: AutoProberStrategy::Delay(Duration::from_millis(100)),
+                ..Default::default()
+            })
+            .with_metrics(metrics.clone())
+            .spawn();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let sleeping = metrics.snapshot().backoff;
+        handle.await.unwrap();
+
+        // ASSERT
+        assert_eq!(sleeping, Duration::from_millis(100));
+        assert_eq!(metrics.snapshot().backoff, Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn tells_once_when_it_stalls_and_when_it_recovers() {
+        // ARRANGE
+        let mut probes = 0;
+        let mut prober = MockProber::<MockSentinelStore<_>, (), MockProcessor>::new();
+        prober.expect_probe().times(3).returning(move || {
+            probes += 1;
+            match probes {
+                1 | 2 => ProbeResult::Empty,
+                _ => ProbeResult::Success,
+            }
+        });
+        let auto = prober
+            .into_auto(AutoProberCfg {
+                on_success: AutoProberStrategy::Abort,
+                on_empty: AutoProberStrategy::Delay(Duration::from_millis(150)),
+                ..Default::default()
+            })
+            .with_stall_threshold(Duration::from_millis(50));
+        let mut events = auto.subscribe();
+
+        // ACT
         auto.spawn().await.unwrap();
-        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+        // ASSERT
+        let mut received = Vec::new();
+        while let Some(event) = events.recv().await {
+            received.push(event);
+        }
+        let stalls = received
+            .iter()
+            .filter(|it| matches!(it, AutoProberEvent::Stalled { .. }))
+            .count();
+        assert_eq!(stalls, 1);
+        // it stalls while waiting after the first probe, not when probing again
+        let stalled_at = received
+            .iter()
+            .position(|it| matches!(it, AutoProberEvent::Stalled { .. }))
+            .unwrap();
+        assert_eq!(
+            received[stalled_at + 1],
+            AutoProberEvent::ProbeStarted { attempt: 2 }
+        );
+        assert!(matches!(
+            received[received.len() - 2],
+            AutoProberEvent::Recovered { stalled_for } if stalled_for >= Duration::from_millis(300)
+        ));
     }
 }
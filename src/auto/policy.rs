@@ -0,0 +1,122 @@
+use std::{collections::VecDeque, time::Duration};
+
+use crate::{ProbeOutcome, ProbeResult};
+
+use super::{circuit::CircuitState, exit::ProbeSummary};
+
+/// Decides what an [`AutoProber`](super::AutoProber) does after each probe.
+///
+/// [`AutoProberCfg`](super::strategy::AutoProberCfg) and every
+/// [`AutoProberStrategy`](super::strategy::AutoProberStrategy) are policies, but anything else
+/// can be plugged in with [`Prober::into_auto_with`](crate::Prober::into_auto_with).
+pub trait SchedulePolicy: Send {
+    /// What to do after `result`, which is already the latest outcome in `history`
+    fn decide(&mut self, result: &ProbeResult, history: &ProbeHistory) -> Action;
+
+    /// Called once the sleep from [`Action::Sleep`] is over, right before probing again
+    fn woke(&mut self) {}
+
+    /// Starts over any state kept between probes
+    fn reset(&mut self) {}
+
+    /// The state of its circuit breaker, if it has one, to show in the prober's status
+    fn circuit(&self) -> Option<CircuitState> {
+        None
+    }
+}
+
+/// What to do after a probe
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    /// Wait this long before probing again
+    Sleep(Duration),
+    /// Probe again right away
+    Continue,
+    Stop(StopReason),
+}
+
+/// Why a [`SchedulePolicy`] stopped the prober, which ends up in its
+/// [`ExitReason`](super::exit::ExitReason)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StopReason {
+    /// Stop because of the last outcome, e.g. there was nothing to process
+    Abort,
+    /// Gave up after too many attempts
+    RetriesExhausted,
+    /// Anything else
+    Other(String),
+}
+
+/// The latest outcomes of an [`AutoProber`](super::AutoProber)
+#[derive(Clone, Debug, Default)]
+pub struct ProbeHistory {
+    recent: VecDeque<ProbeOutcome>,
+    summary: ProbeSummary,
+}
+
+impl ProbeHistory {
+    /// How many outcomes are kept around
+    pub const CAPACITY: usize = 64;
+
+    pub(crate) fn record(&mut self, outcome: ProbeOutcome) {
+        if self.recent.len() == Self::CAPACITY {
+            self.recent.pop_back();
+        }
+        self.recent.push_front(outcome);
+        self.summary.record(outcome);
+    }
+
+    pub fn last(&self) -> Option<ProbeOutcome> {
+        self.recent.front().copied()
+    }
+
+    /// The latest outcomes, newest first
+    pub fn recent(&self) -> impl Iterator<Item = ProbeOutcome> + '_ {
+        self.recent.iter().copied()
+    }
+
+    /// How many times in a row the last outcome came out, up to [`Self::CAPACITY`]
+    pub fn streak(&self) -> usize {
+        self.last()
+            .map(|last| self.recent().take_while(|it| *it == last).count())
+            .unwrap_or(0)
+    }
+
+    /// Counters for every probe so far, not just the recent ones
+    pub fn summary(&self) -> &ProbeSummary {
+        &self.summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_streaks() {
+        let mut history = ProbeHistory::default();
+        assert_eq!(history.streak(), 0);
+
+        history.record(ProbeOutcome::Success);
+        history.record(ProbeOutcome::Empty);
+        history.record(ProbeOutcome::Empty);
+
+        assert_eq!(history.last(), Some(ProbeOutcome::Empty));
+        assert_eq!(history.streak(), 2);
+        assert_eq!(history.summary().probes, 3);
+    }
+
+    #[test]
+    fn keeps_only_recent_outcomes() {
+        let mut history = ProbeHistory::default();
+        for _ in 0..ProbeHistory::CAPACITY + 10 {
+            history.record(ProbeOutcome::Empty);
+        }
+
+        assert_eq!(history.recent().count(), ProbeHistory::CAPACITY);
+        assert_eq!(
+            history.summary().empties,
+            ProbeHistory::CAPACITY as u64 + 10
+        );
+    }
+}
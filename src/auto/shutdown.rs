@@ -0,0 +1,65 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use event_listener::Event;
+
+use crate::notify;
+
+/// Tells running [`AutoProber`](super::AutoProber)s to stop.
+///
+/// On shutdown, a prober finishes the probe it's running, if any, including its commit, and then
+/// returns. Any delay or backoff it's sleeping through is cut short. Clones share their state, so
+/// a single token can stop many probers.
+#[derive(Clone, Debug, Default)]
+pub struct ShutdownToken {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    triggered: AtomicBool,
+    event: Event,
+}
+
+impl ShutdownToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn shutdown(&self) {
+        self.inner.triggered.store(true, Ordering::Release);
+        self.inner.event.notify(usize::MAX);
+    }
+
+    pub fn is_shutdown(&self) -> bool {
+        self.inner.triggered.load(Ordering::Acquire)
+    }
+
+    /// Waits until [`ShutdownToken::shutdown`] is called
+    pub async fn wait(&self) {
+        notify::wait_for(&self.inner.event, || self.is_shutdown()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn wakes_up_waiters() {
+        let token = ShutdownToken::new();
+        let waiter = tokio::spawn({
+            let token = token.clone();
+            async move { token.wait().await }
+        });
+
+        token.shutdown();
+
+        tokio::time::timeout(tokio::time::Duration::from_secs(1), waiter)
+            .await
+            .expect("waiter should be woken up")
+            .unwrap();
+    }
+}
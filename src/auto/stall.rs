@@ -0,0 +1,129 @@
+use std::time::{Duration, Instant};
+
+use super::{
+    event::{AutoProberEvent, EventSender},
+    handle::Control,
+};
+
+/// Keeps track of whether a prober went too long without committing anything
+pub(crate) struct StallWatch {
+    threshold: Duration,
+    /// When it last committed, or started if it hasn't yet
+    last_commit_at: Instant,
+    stalled: bool,
+}
+
+/// How a [`StallWatch`] changed
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum StallChange {
+    /// Nothing was committed for this long
+    Stalled(Duration),
+    /// It committed again after this long without committing
+    Recovered(Duration),
+}
+
+impl StallWatch {
+    pub(crate) fn new(threshold: Duration) -> Self {
+        Self {
+            threshold,
+            last_commit_at: Instant::now(),
+            stalled: false,
+        }
+    }
+
+    pub(crate) fn committed(&mut self) -> Option<StallChange> {
+        let since_commit = self.last_commit_at.elapsed();
+        self.last_commit_at = Instant::now();
+
+        std::mem::take(&mut self.stalled).then_some(StallChange::Recovered(since_commit))
+    }
+
+    /// Only tells it stalled the first time it's checked past the threshold
+    pub(crate) fn check(&mut self) -> Option<StallChange> {
+        let since_commit = self.last_commit_at.elapsed();
+        if self.stalled || since_commit < self.threshold {
+            return None;
+        }
+
+        self.stalled = true;
+        Some(StallChange::Stalled(since_commit))
+    }
+
+    /// How long until it stalls, `None` if it already has
+    pub(crate) fn until_stalled(&self) -> Option<Duration> {
+        (!self.stalled).then(|| self.threshold.saturating_sub(self.last_commit_at.elapsed()))
+    }
+}
+
+/// Sleeps like [`Control::sleep`], but still tells if it stalls in the meantime
+pub(crate) async fn sleep<Sentinel: Clone>(
+    control: &Control,
+    mut stall: Option<&mut StallWatch>,
+    events: &EventSender<Sentinel>,
+    delay: Duration,
+) {
+    let mut left = delay;
+    loop {
+        let step = stall
+            .as_deref()
+            .and_then(StallWatch::until_stalled)
+            .map_or(left, |it| it.min(left));
+        let started = Instant::now();
+        control.sleep(step).await;
+        // a trigger or a shutdown cut it short
+        let interrupted = started.elapsed() < step;
+
+        if let Some(change) = stall.as_deref_mut().and_then(StallWatch::check) {
+            report(control, events, change);
+        }
+        if interrupted || step == left {
+            return;
+        }
+        left -= step;
+    }
+}
+
+pub(crate) fn report<Sentinel: Clone>(
+    control: &Control,
+    events: &EventSender<Sentinel>,
+    change: StallChange,
+) {
+    match change {
+        StallChange::Stalled(since_commit) => {
+            tracing::warn!(
+                event = "probe-stall",
+                "nothing was committed for {since_commit:?}"
+            );
+            events.emit(AutoProberEvent::Stalled { since_commit });
+            control.set_stalled(true);
+        }
+        StallChange::Recovered(stalled_for) => {
+            tracing::info!(
+                event = "probe-stall",
+                "committing again after {stalled_for:?}"
+            );
+            events.emit(AutoProberEvent::Recovered { stalled_for });
+            control.set_stalled(false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tells_once_when_it_stalls_and_recovers() {
+        let mut watch = StallWatch::new(Duration::from_millis(10));
+        assert_eq!(watch.check(), None);
+
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(matches!(watch.check(), Some(StallChange::Stalled(_))));
+        assert_eq!(watch.check(), None);
+        assert_eq!(watch.until_stalled(), None);
+
+        assert!(matches!(watch.committed(), Some(StallChange::Recovered(_))));
+        assert_eq!(watch.committed(), None);
+        assert!(watch.until_stalled().is_some());
+    }
+}
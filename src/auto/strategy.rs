@@ -1,22 +1,200 @@
 use std::time::Duration;
 
+use crate::{proc::error::ErrorClass, ProbeError, ProbeOutcome, ProbeResult};
+
+use super::{
+    adaptive::AdaptivePolling,
+    circuit::{CircuitBreaker, CircuitState},
+    policy::{Action, ProbeHistory, SchedulePolicy, StopReason},
+};
+
 /// What the autoprober should do in specific situations
 #[derive(Clone, Debug)]
 pub enum AutoProberStrategy {
-    /// Stop completely. Panics for errors.
+    /// Stop completely
     Abort,
-    /// Wait this many seconds before proceeding.
-    DelaySecs(u32),
+    /// Wait this long before proceeding.
+    Delay(Duration),
     /// Continue instantly
     Continue,
     /// Backoff
     Backoff(BackoffStrategy),
+    /// Stop hammering after too many failures in a row
+    CircuitBreaker(CircuitBreaker),
+    /// Wait longer each time, until the outcome changes
+    Adaptive(AdaptivePolling),
+    /// Wait exactly as long as a [rate-limited](ErrorClass::RateLimited) error asks for, or
+    /// continue instantly for anything else
+    RetryAfter,
+}
+
+impl SchedulePolicy for AutoProberStrategy {
+    fn decide(&mut self, result: &ProbeResult, history: &ProbeHistory) -> Action {
+        match self {
+            Self::Abort => Action::Stop(StopReason::Abort),
+            Self::Delay(delay) => Action::Sleep(*delay),
+            Self::Continue => Action::Continue,
+            Self::Backoff(backoff) => backoff.decide(result, history),
+            Self::CircuitBreaker(breaker) => breaker.decide(result, history),
+            Self::Adaptive(polling) => polling.decide(result, history),
+            Self::RetryAfter => match result.error().and_then(ProbeError::class) {
+                Some(ErrorClass::RateLimited { retry_after }) => Action::Sleep(retry_after),
+                _ => Action::Continue,
+            },
+        }
+    }
+
+    fn woke(&mut self) {
+        if let Self::CircuitBreaker(breaker) = self {
+            breaker.woke();
+        }
+    }
+
+    fn reset(&mut self) {
+        match self {
+            Self::Backoff(backoff) => backoff.reset(),
+            Self::CircuitBreaker(breaker) => breaker.reset(),
+            Self::Adaptive(polling) => polling.reset(),
+            _ => {}
+        }
+    }
+
+    fn circuit(&self) -> Option<CircuitState> {
+        match self {
+            Self::CircuitBreaker(breaker) => breaker.circuit(),
+            _ => None,
+        }
+    }
 }
 
 pub struct AutoProberCfg {
     pub on_success: AutoProberStrategy,
     pub on_empty: AutoProberStrategy,
-    pub on_error: AutoProberStrategy,
+    /// For failures reading or committing the sentinel
+    pub on_store_error: AutoProberStrategy,
+    /// For failures in the processor
+    pub on_processor_error: AutoProberStrategy,
+    /// Overrides the two above for [classified](ErrorClass) errors
+    pub on_error_class: ErrorClassCfg,
+    /// For someone else changing the stored sentinel while probing. Waits a little by default, so
+    /// a store that keeps conflicting isn't hammered.
+    pub on_conflict: AutoProberStrategy,
+    /// Randomizes every delay so many probers don't poll in lockstep. Rate limits are waited out
+    /// exactly, and [backoffs](BackoffStrategy::with_jitter) have their own jitter instead.
+    pub jitter: Jitter,
+}
+
+impl AutoProberCfg {
+    /// Handle store and processor errors the same way, each with its own state, except for a
+    /// [`CircuitBreaker`] which counts them together
+    pub fn with_on_error(mut self, strategy: AutoProberStrategy) -> Self {
+        self.on_store_error = strategy.clone();
+        self.on_processor_error = strategy;
+        self
+    }
+
+    /// Probe continuously while there's something to process, and slow down step by step while
+    /// there isn't, going back to full speed as soon as something comes up
+    pub fn adaptive(polling: AdaptivePolling) -> Self {
+        Self {
+            on_success: AutoProberStrategy::Continue,
+            on_empty: AutoProberStrategy::Adaptive(polling),
+            ..Default::default()
+        }
+    }
+
+    fn strategy_mut(&mut self, result: &ProbeResult) -> &mut AutoProberStrategy {
+        match result {
+            ProbeResult::Success => &mut self.on_success,
+            ProbeResult::Empty => &mut self.on_empty,
+            ProbeResult::Error(err) | ProbeResult::PendingCommit(err) => err
+                .class()
+                .and_then(|class| self.on_error_class.strategy_mut(class))
+                .unwrap_or(match err {
+                    ProbeError::Store(_) => &mut self.on_store_error,
+                    ProbeError::Processor(_) => &mut self.on_processor_error,
+                }),
+            ProbeResult::Conflict => &mut self.on_conflict,
+        }
+    }
+
+    fn strategies(&self) -> impl Iterator<Item = &AutoProberStrategy> {
+        [
+            &self.on_store_error,
+            &self.on_processor_error,
+            &self.on_empty,
+            &self.on_success,
+            &self.on_conflict,
+        ]
+        .into_iter()
+        .chain(self.on_error_class.strategies())
+    }
+
+    fn strategies_mut(&mut self) -> impl Iterator<Item = &mut AutoProberStrategy> {
+        [
+            &mut self.on_store_error,
+            &mut self.on_processor_error,
+            &mut self.on_empty,
+            &mut self.on_success,
+            &mut self.on_conflict,
+        ]
+        .into_iter()
+        .chain(self.on_error_class.strategies_mut())
+    }
+
+    /// Strategies start over once the outcome they handle stops coming
+    fn reset_all_but(&mut self, outcome: ProbeOutcome) {
+        if outcome != ProbeOutcome::Success {
+            self.on_success.reset();
+        }
+        if outcome != ProbeOutcome::Empty {
+            self.on_empty.reset();
+        }
+        if outcome != ProbeOutcome::Conflict {
+            self.on_conflict.reset();
+        }
+        if !matches!(outcome, ProbeOutcome::Error | ProbeOutcome::PendingCommit) {
+            self.on_store_error.reset();
+            self.on_processor_error.reset();
+            self.on_error_class
+                .strategies_mut()
+                .for_each(|it| it.reset());
+        }
+    }
+}
+
+/// Conflicts don't make the other strategies start over, since they say nothing about how
+/// processing goes
+impl SchedulePolicy for AutoProberCfg {
+    fn decide(&mut self, result: &ProbeResult, history: &ProbeHistory) -> Action {
+        if result.outcome() != ProbeOutcome::Conflict {
+            self.reset_all_but(result.outcome());
+        }
+
+        let jitter = self.jitter;
+        match self.strategy_mut(result) {
+            // rate limits are waited out exactly, and backoffs jitter themselves
+            strategy @ (AutoProberStrategy::RetryAfter | AutoProberStrategy::Backoff(_)) => {
+                strategy.decide(result, history)
+            }
+            strategy => match strategy.decide(result, history) {
+                Action::Sleep(delay) => Action::Sleep(jitter.apply(delay)),
+                action => action,
+            },
+        }
+    }
+
+    fn woke(&mut self) {
+        self.strategies_mut().for_each(|it| it.woke());
+    }
+
+    fn reset(&mut self) {
+        self.strategies_mut().for_each(|it| it.reset());
+    }
+
+    fn circuit(&self) -> Option<CircuitState> {
+        self.strategies().find_map(|it| it.circuit())
+    }
 }
 
 impl Default for AutoProberCfg {
@@ -24,7 +202,94 @@ impl Default for AutoProberCfg {
         Self {
             on_success: AutoProberStrategy::Continue,
             on_empty: AutoProberStrategy::Abort,
-            on_error: AutoProberStrategy::Abort,
+            on_store_error: AutoProberStrategy::Abort,
+            on_processor_error: AutoProberStrategy::Abort,
+            on_error_class: ErrorClassCfg::default(),
+            on_conflict: AutoProberStrategy::Delay(Duration::from_millis(100)),
+            jitter: Jitter::None,
+        }
+    }
+}
+
+/// What to do for errors of each [`ErrorClass`]. The ones left as `None` are handled by
+/// `on_store_error` or `on_processor_error`.
+#[derive(Clone, Debug)]
+pub struct ErrorClassCfg {
+    pub transient: Option<AutoProberStrategy>,
+    /// Stops right away by default
+    pub permanent: Option<AutoProberStrategy>,
+    /// Waits exactly as long as asked by default
+    pub rate_limited: Option<AutoProberStrategy>,
+    /// Continues right away by default, since the sentinel was already skipped. Errors that
+    /// couldn't be skipped lose the class and are handled as unclassified ones.
+    pub skip: Option<AutoProberStrategy>,
+}
+
+impl ErrorClassCfg {
+    fn strategy_mut(&mut self, class: ErrorClass) -> Option<&mut AutoProberStrategy> {
+        match class {
+            ErrorClass::Transient => self.transient.as_mut(),
+            ErrorClass::Permanent => self.permanent.as_mut(),
+            ErrorClass::RateLimited { .. } => self.rate_limited.as_mut(),
+            ErrorClass::Skip => self.skip.as_mut(),
+        }
+    }
+
+    fn strategies(&self) -> impl Iterator<Item = &AutoProberStrategy> {
+        [
+            &self.transient,
+            &self.permanent,
+            &self.rate_limited,
+            &self.skip,
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    fn strategies_mut(&mut self) -> impl Iterator<Item = &mut AutoProberStrategy> {
+        [
+            &mut self.transient,
+            &mut self.permanent,
+            &mut self.rate_limited,
+            &mut self.skip,
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+impl Default for ErrorClassCfg {
+    fn default() -> Self {
+        Self {
+            transient: None,
+            permanent: Some(AutoProberStrategy::Abort),
+            rate_limited: Some(AutoProberStrategy::RetryAfter),
+            skip: Some(AutoProberStrategy::Continue),
+        }
+    }
+}
+
+/// How to randomize delays
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Jitter {
+    /// Use delays as they are
+    #[default]
+    None,
+    /// Anything between no delay and the full delay
+    Full,
+    /// Up to this fraction of the delay more or less, e.g. `0.1` for ±10%
+    Proportional(f64),
+}
+
+impl Jitter {
+    pub fn apply(self, delay: Duration) -> Duration {
+        match self {
+            Self::None => delay,
+            Self::Full => delay.mul_f64(fastrand::f64()),
+            Self::Proportional(fraction) => {
+                let factor = 1.0 + fraction * (fastrand::f64() * 2.0 - 1.0);
+                delay.mul_f64(factor.max(0.0))
+            }
         }
     }
 }
@@ -33,21 +298,60 @@ impl Default for AutoProberCfg {
 pub struct BackoffStrategy {
     backoff_template: exponential_backoff::Backoff,
     current_backoff: exponential_backoff::IntoIter,
+    /// Kept around because the template doesn't expose it
+    initial_delay: Duration,
 }
 
 impl BackoffStrategy {
-    pub fn new(attempts: u32, delay_secs: u32) -> Self {
-        let backoff = exponential_backoff::Backoff::new(
-            attempts,
-            Duration::from_secs(delay_secs.into()),
-            None,
-        );
+    /// Up to `attempts` probes, starting with `delay` between them and doubling it each time
+    pub fn new(attempts: u32, delay: Duration) -> Self {
+        let backoff = exponential_backoff::Backoff::new(attempts, delay, None);
         Self {
             current_backoff: backoff.clone().into_iter(),
             backoff_template: backoff,
+            initial_delay: delay,
         }
     }
 
+    /// Never runs out of attempts. You probably want a [`max delay`](Self::with_max_delay) too.
+    pub fn unlimited(delay: Duration) -> Self {
+        Self::new(u32::MAX, delay)
+    }
+
+    /// Don't let the delay grow past `max`
+    ///
+    /// # Panics
+    ///
+    /// If `max` is shorter than the delay it starts with
+    pub fn with_max_delay(mut self, max: Duration) -> Self {
+        assert!(
+            max >= self.initial_delay,
+            "max delay is shorter than the initial delay"
+        );
+        self.backoff_template.set_max(max);
+        self.reset();
+        self
+    }
+
+    /// Grow the delay by this factor on each attempt instead of doubling it
+    pub fn with_multiplier(mut self, multiplier: u32) -> Self {
+        self.backoff_template.set_factor(multiplier);
+        self.reset();
+        self
+    }
+
+    /// Randomize each delay by this fraction of it, `0.3` by default. There's no turning it off,
+    /// but [`AutoProberCfg::jitter`] isn't applied on top of it.
+    ///
+    /// # Panics
+    ///
+    /// If `jitter` isn't between `0` and `1`, both excluded
+    pub fn with_jitter(mut self, jitter: f32) -> Self {
+        self.backoff_template.set_jitter(jitter);
+        self.reset();
+        self
+    }
+
     pub fn next_sleep(&mut self) -> Option<Duration> {
         // I flatten it because I don't like much its Option<Option<>> approach
         self.current_backoff.next().flatten()
@@ -57,3 +361,85 @@ impl BackoffStrategy {
         self.current_backoff = self.backoff_template.clone().into_iter()
     }
 }
+
+impl SchedulePolicy for BackoffStrategy {
+    fn decide(&mut self, _result: &ProbeResult, _history: &ProbeHistory) -> Action {
+        self.next_sleep()
+            .map_or(Action::Stop(StopReason::RetriesExhausted), Action::Sleep)
+    }
+
+    fn reset(&mut self) {
+        BackoffStrategy::reset(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jitter_stays_within_bounds() {
+        let delay = Duration::from_millis(500);
+        for _ in 0..100 {
+            assert!(Jitter::Full.apply(delay) <= delay);
+
+            let jittered = Jitter::Proportional(0.1).apply(delay);
+            assert!(jittered >= Duration::from_millis(450));
+            assert!(jittered <= Duration::from_millis(550));
+        }
+    }
+
+    #[test]
+    fn backoff_keeps_sub_second_delays() {
+        let mut backoff = BackoffStrategy::new(3, Duration::from_millis(500));
+
+        assert!(backoff
+            .next_sleep()
+            .is_some_and(|it| it >= Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn backoff_respects_max_delay() {
+        let mut backoff = BackoffStrategy::unlimited(Duration::from_millis(10))
+            .with_multiplier(10)
+            .with_max_delay(Duration::from_millis(50));
+
+        for _ in 0..20 {
+            assert!(backoff
+                .next_sleep()
+                .is_some_and(|it| it <= Duration::from_millis(50)));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "max delay")]
+    fn backoff_rejects_max_delay_below_initial_delay() {
+        BackoffStrategy::unlimited(Duration::from_secs(1)).with_max_delay(Duration::from_millis(1));
+    }
+
+    #[test]
+    fn backoff_starts_over_after_reset() {
+        let mut strategy = AutoProberStrategy::Backoff(BackoffStrategy::new(2, Duration::ZERO));
+        let AutoProberStrategy::Backoff(backoff) = &mut strategy else {
+            unreachable!()
+        };
+        assert!(backoff.next_sleep().is_some());
+        assert!(backoff.next_sleep().is_none());
+
+        strategy.reset();
+        let AutoProberStrategy::Backoff(backoff) = &mut strategy else {
+            unreachable!()
+        };
+        assert!(backoff.next_sleep().is_some());
+    }
+
+    #[test]
+    fn waits_after_conflicts_by_default() {
+        let mut cfg = AutoProberCfg::default();
+
+        assert_eq!(
+            cfg.decide(&ProbeResult::Conflict, &ProbeHistory::default()),
+            Action::Sleep(Duration::from_millis(100))
+        );
+    }
+}
@@ -0,0 +1,32 @@
+use std::time::Duration;
+
+/// How a [`Prober`](crate::Prober) retries a commit that failed after the processor already
+/// produced the next sentinel.
+///
+/// These retries happen within the same probe, which can't be shut down while it waits between
+/// them. If all of them fail, the sentinel is kept as a pending commit and the next probe retries
+/// committing it without running the processor again. That's all that happens by default.
+#[derive(Clone, Debug)]
+pub struct CommitRetryPolicy {
+    /// How many times to retry a failed commit before giving up on this probe
+    pub attempts: u32,
+    /// How long to wait between retries
+    pub delay: Duration,
+}
+
+impl CommitRetryPolicy {
+    pub fn new(attempts: u32, delay: Duration) -> Self {
+        Self { attempts, delay }
+    }
+
+    /// Never retry within a probe, just keep the sentinel pending for the next one
+    pub fn none() -> Self {
+        Self::new(0, Duration::ZERO)
+    }
+}
+
+impl Default for CommitRetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
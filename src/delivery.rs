@@ -0,0 +1,16 @@
+//! Delivery guarantees for probers
+
+/// In which order a [`Prober`](crate::Prober) executes the side effects of a probe and commits
+/// the next sentinel.
+///
+/// This only makes a difference for two-phase processors, that is, those that plan the next
+/// sentinel in [`Processor::next`](crate::proc::Processor::next) and do the actual work in
+/// [`Processor::execute`](crate::proc::Processor::execute).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DeliveryMode {
+    /// Execute first, then commit. If the commit fails, the same sentinel is executed again.
+    #[default]
+    AtLeastOnce,
+    /// Commit first, then execute. If the execution fails, the sentinel is not executed again.
+    AtMostOnce,
+}
@@ -0,0 +1,133 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::alias::DynErr;
+use crate::runtime::{Runtime, RuntimeImpl};
+
+use super::Lease;
+
+/// A lease held in a lock file that contains its holder and expiry timestamp
+pub struct FileLease {
+    file: <RuntimeImpl as Runtime>::File,
+    owner: String,
+    ttl: Duration,
+}
+
+impl FileLease {
+    /// Opens a lease at `file_path` that lasts `ttl` after each renewal
+    pub async fn open(
+        file_path: &str,
+        ttl: Duration,
+    ) -> Result<Self, <RuntimeImpl as Runtime>::Err> {
+        Ok(Self {
+            file: RuntimeImpl::open_file(file_path).await?,
+            owner: default_owner(),
+            ttl,
+        })
+    }
+
+    /// Identifies the holder with `owner` instead of a generated id. Each replica must use a
+    /// different one.
+    pub fn with_owner(mut self, owner: impl Into<String>) -> Self {
+        self.owner = owner.into();
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl Lease for FileLease {
+    async fn acquire(&mut self) -> Result<bool, DynErr> {
+        let contents = RuntimeImpl::read_string(&self.file).await?;
+
+        let held_by_other = parse_lock(&contents).is_some_and(|(holder, expires_at_millis)| {
+            holder != self.owner && expires_at_millis > now_millis()
+        });
+        if held_by_other {
+            return Ok(false);
+        }
+
+        let expires_at_millis = now_millis() + self.ttl.as_millis();
+        let new_contents = format!("{} {expires_at_millis}", self.owner);
+
+        // somebody else might have taken it since it was read
+        Ok(RuntimeImpl::compare_and_write(&self.file, &contents, &new_contents).await?)
+    }
+
+    async fn release(&mut self) -> Result<(), DynErr> {
+        let contents = RuntimeImpl::read_string(&self.file).await?;
+
+        if parse_lock(&contents).is_some_and(|(holder, _)| holder == self.owner) {
+            RuntimeImpl::compare_and_write(&self.file, &contents, "").await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses the holder and expiry of a lock file. Anything unparseable is treated as no lock.
+fn parse_lock(contents: &str) -> Option<(&str, u128)> {
+    let (holder, expires_at_millis) = contents.trim().rsplit_once(' ')?;
+    Some((holder, expires_at_millis.parse().ok()?))
+}
+
+fn now_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+}
+
+fn default_owner() -> String {
+    format!(
+        "{}-{}",
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::distributions::DistString as _;
+
+    use super::*;
+
+    fn lock_path() -> String {
+        let test_id = rand::distributions::Alphanumeric.sample_string(&mut rand::thread_rng(), 10);
+        format!("/tmp/mrprober-test-{test_id}.lock")
+    }
+
+    #[tokio::test]
+    async fn only_one_holder_at_a_time() {
+        let path = lock_path();
+        let mut first = FileLease::open(&path, Duration::from_secs(60))
+            .await
+            .unwrap();
+        let mut second = FileLease::open(&path, Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        assert!(first.acquire().await.unwrap());
+        assert!(!second.acquire().await.unwrap());
+        // renewing
+        assert!(first.acquire().await.unwrap());
+
+        first.release().await.unwrap();
+        assert!(second.acquire().await.unwrap());
+        assert!(!first.acquire().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn expired_lease_can_be_taken_over() {
+        let path = lock_path();
+        let mut first = FileLease::open(&path, Duration::ZERO).await.unwrap();
+        let mut second = FileLease::open(&path, Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        assert!(first.acquire().await.unwrap());
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        assert!(second.acquire().await.unwrap());
+    }
+}
@@ -0,0 +1,99 @@
+//! Leases, so only one of several probers sharing a store probes at a time
+
+pub mod file;
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use event_listener::Event;
+
+use crate::{
+    alias::DynErr,
+    auto::shutdown::ShutdownToken,
+    notify,
+    runtime::{Runtime as _, RuntimeImpl},
+};
+
+/// A time-limited lease that only one holder can have at a time.
+///
+/// If the holder stops renewing it, it expires and someone else can acquire it.
+#[async_trait::async_trait]
+pub trait Lease {
+    /// Acquires the lease, or renews it if it's already held. Returns whether it's held.
+    async fn acquire(&mut self) -> Result<bool, DynErr>;
+
+    /// Gives the lease up if it's held, so others don't have to wait for it to expire
+    async fn release(&mut self) -> Result<(), DynErr>;
+}
+
+/// Keeps renewing a lease in the background until it's dropped, at which point the lease is
+/// released
+pub(crate) struct LeaseKeeper {
+    held: Arc<AtomicBool>,
+    /// Notified whenever `held` changes
+    changed: Arc<Event>,
+    /// Wakes the renewing loop up on drop, so the lease is released right away
+    stopped: ShutdownToken,
+}
+
+impl LeaseKeeper {
+    pub(crate) fn spawn(mut lease: Box<dyn Lease + Send>, renew_every: Duration) -> Self {
+        let held = Arc::new(AtomicBool::new(false));
+        let changed = Arc::new(Event::new());
+        let stopped = ShutdownToken::new();
+
+        let keeper = RuntimeImpl::spawn({
+            let held = Arc::clone(&held);
+            let changed = Arc::clone(&changed);
+            let stopped = stopped.clone();
+            async move {
+                while !stopped.is_shutdown() {
+                    let is_held = lease.acquire().await.unwrap_or_else(|err| {
+                        tracing::error!(event = "lease-error", err = ?err, "couldn't acquire lease");
+                        false
+                    });
+
+                    if is_held != held.swap(is_held, Ordering::AcqRel) {
+                        tracing::info!(event = "lease-changed", held = is_held);
+                        changed.notify(usize::MAX);
+                    }
+
+                    futures_lite::future::or(RuntimeImpl::sleep(renew_every), stopped.wait()).await;
+                }
+
+                held.store(false, Ordering::Release);
+                if let Err(err) = lease.release().await {
+                    tracing::error!(event = "lease-error", err = ?err, "couldn't release lease");
+                }
+            }
+        });
+        RuntimeImpl::detach(keeper);
+
+        Self {
+            held,
+            changed,
+            stopped,
+        }
+    }
+
+    pub(crate) fn is_held(&self) -> bool {
+        self.held.load(Ordering::Acquire)
+    }
+
+    /// Waits until the lease is held, which is as soon as it's first acquired if nobody else
+    /// holds it
+    pub(crate) async fn until_held(&self) {
+        notify::wait_for(&self.changed, || self.is_held()).await
+    }
+}
+
+impl Drop for LeaseKeeper {
+    fn drop(&mut self) {
+        self.stopped.shutdown();
+    }
+}
@@ -1,21 +1,77 @@
 mod alias;
 pub mod auto;
+pub mod commit;
+pub mod delivery;
+pub mod lease;
+pub mod metrics;
+mod notify;
 pub mod preconf;
 pub mod proc;
+pub mod progress;
+pub mod quarantine;
 pub mod runtime;
 pub mod store;
 
-use std::marker::PhantomData;
+use std::time::{Duration, Instant};
 
-use alias::DynErr;
-use proc::Processor;
-use store::SentinelStore;
+use alias::{DynErr, SentinelFormat};
+use commit::CommitRetryPolicy;
+use delivery::DeliveryMode;
+use proc::{
+    error::{ClassifiedError, ErrorClass},
+    Processor,
+};
+use progress::{NoProgress, OnUnchanged, ProgressChecks};
+use quarantine::{Quarantine, QuarantineSink, Quarantined};
+use runtime::{Runtime as _, RuntimeImpl};
+use store::{CommitOutcome, SentinelStore};
 use thiserror::Error;
+use tracing::{field, Instrument as _};
 
 pub struct Prober<Store, Sentinel, Proc> {
     store: Store,
     processor: Proc,
-    _sentinel: PhantomData<Sentinel>,
+    commit_retry: CommitRetryPolicy,
+    delivery: DeliveryMode,
+    conditional_commits: bool,
+    /// A sentinel that the processor already produced but that couldn't be committed
+    pending_commit: Option<PendingCommit<Sentinel>>,
+    quarantine: Option<Quarantine<Sentinel>>,
+    progress: ProgressChecks<Sentinel>,
+    /// What the processor suggested in the last probe
+    retry_hint: Option<Duration>,
+    /// What's been gathered for the report of the probe that's running
+    ongoing: OngoingProbe<Sentinel>,
+    /// Every probe's span is a child of this one
+    span: tracing::Span,
+    /// How many probes were run, counting the one that's running
+    attempts: u64,
+    /// How sentinels show up in the probe spans, if at all
+    trace_sentinel: Option<SentinelFormat<Sentinel>>,
+}
+
+struct OngoingProbe<Sentinel> {
+    previous: Option<Sentinel>,
+    committed: Option<Sentinel>,
+    processor_time: Duration,
+    commit_time: Duration,
+}
+
+impl<Sentinel> Default for OngoingProbe<Sentinel> {
+    fn default() -> Self {
+        Self {
+            previous: None,
+            committed: None,
+            processor_time: Duration::ZERO,
+            commit_time: Duration::ZERO,
+        }
+    }
+}
+
+struct PendingCommit<Sentinel> {
+    /// What the store held when the sentinel was produced
+    expected: Option<Sentinel>,
+    sentinel: Sentinel,
 }
 
 impl<Store, Sentinel, Proc> Prober<Store, Sentinel, Proc> {
@@ -23,32 +79,374 @@ impl<Store, Sentinel, Proc> Prober<Store, Sentinel, Proc> {
         Self {
             store: storage,
             processor,
-            _sentinel: PhantomData,
+            commit_retry: CommitRetryPolicy::default(),
+            delivery: DeliveryMode::default(),
+            conditional_commits: false,
+            pending_commit: None,
+            quarantine: None,
+            progress: ProgressChecks::default(),
+            retry_hint: None,
+            ongoing: OngoingProbe::default(),
+            span: tracing::Span::none(),
+            attempts: 0,
+            trace_sentinel: None,
         }
     }
+
+    /// Runs every probe in a `prober` span with this `name`, to tell it apart from other
+    /// probers. Probes that are run by an [`AutoProber`](auto::AutoProber) have everything it
+    /// logs in there too.
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.span = tracing::info_span!("prober", name);
+        self
+    }
+
+    /// Records sentinels in the probe spans as `format` shows them. They're left out otherwise,
+    /// since they might be sensitive, so `format` can also redact them.
+    pub fn with_traced_sentinels(
+        mut self,
+        format: impl Fn(&Sentinel) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.trace_sentinel = Some(Box::new(format));
+        self
+    }
+
+    /// Sets how failed commits are retried
+    pub fn with_commit_retry(mut self, commit_retry: CommitRetryPolicy) -> Self {
+        self.commit_retry = commit_retry;
+        self
+    }
+
+    /// Sets the order in which side effects are executed and sentinels committed
+    pub fn with_delivery(mut self, delivery: DeliveryMode) -> Self {
+        self.delivery = delivery;
+        self
+    }
+
+    /// Only commit if the stored sentinel is still the one this prober read, reporting a
+    /// [`ProbeResult::Conflict`] otherwise. The store must support
+    /// [`SentinelStore::commit_if`].
+    pub fn with_conditional_commits(mut self) -> Self {
+        self.conditional_commits = true;
+        self
+    }
+
+    /// Whether there's a sentinel waiting to be committed
+    pub fn has_pending_commit(&self) -> bool {
+        self.pending_commit.is_some()
+    }
+
+    /// How long the processor suggested waiting before probing again, in the last probe
+    pub fn retry_hint(&self) -> Option<Duration> {
+        self.retry_hint
+    }
+
+    /// The span every probe runs in, to put other work related to this prober in it too
+    pub fn span(&self) -> tracing::Span {
+        self.span.clone()
+    }
+}
+
+impl<Store, Sentinel, Proc> Prober<Store, Sentinel, Proc>
+where
+    Sentinel: PartialEq + 'static,
+{
+    /// Catch the processor returning the sentinel it was given, which would otherwise be
+    /// committed as a [`ProbeResult::Success`] and probed again right away with
+    /// [`AutoProberStrategy::Continue`](auto::strategy::AutoProberStrategy::Continue).
+    /// `on_unchanged` tells what the probe comes out as then.
+    pub fn with_unchanged_check(mut self, on_unchanged: OnUnchanged) -> Self {
+        self.progress.unchanged = Some(Box::new(|current, next| current == next));
+        self.progress.on_unchanged = on_unchanged;
+        self
+    }
+
+    /// Once processing fails `after_failures` times in a row on the same sentinel, put it into
+    /// `sink` and skip past it with [`Processor::skip`], if the processor can. It's only put
+    /// there once, even if it can't be skipped and keeps failing.
+    pub fn with_quarantine(
+        mut self,
+        after_failures: u32,
+        sink: impl QuarantineSink<Sentinel> + 'static,
+    ) -> Self {
+        self.quarantine = Some(Quarantine::new(after_failures, sink));
+        self
+    }
+}
+
+impl<Store, Sentinel, Proc> Prober<Store, Sentinel, Proc>
+where
+    Sentinel: Ord + 'static,
+{
+    /// Fail probes where the processor returns a sentinel that comes before the one it was
+    /// given, with a [`NoProgress::Regressed`] processor error
+    pub fn with_regression_check(mut self) -> Self {
+        self.progress.regressed = Some(Box::new(|current, next| next < current));
+        self
+    }
 }
 
 impl<Store, Sentinel, Proc> Prober<Store, Sentinel, Proc>
 where
     Store: SentinelStore<Sentinel> + Send,
     Proc: Processor<Sentinel = Sentinel> + Send,
-    Sentinel: Send,
+    Sentinel: Clone + Send + Sync,
 {
     pub async fn probe(&mut self) -> ProbeResult {
+        self.probe_report().await.result
+    }
+
+    /// Probes just like [`Prober::probe`], but also tells where it moved from and to, and how
+    /// long it took
+    pub async fn probe_report(&mut self) -> ProbeReport<Sentinel> {
+        self.retry_hint = None;
+        self.attempts += 1;
+        let span = self.span.in_scope(|| {
+            tracing::info_span!(
+                "probe",
+                attempt = self.attempts,
+                outcome = field::Empty,
+                previous = field::Empty,
+                committed = field::Empty,
+            )
+        });
+        let result = self.run_probe().instrument(span.clone()).await;
+        let ongoing = std::mem::take(&mut self.ongoing);
+
+        span.record("outcome", field::debug(result.outcome()));
+        if let Some(trace_sentinel) = &self.trace_sentinel {
+            if let Some(previous) = &ongoing.previous {
+                span.record("previous", trace_sentinel(previous));
+            }
+            if let Some(committed) = &ongoing.committed {
+                span.record("committed", trace_sentinel(committed));
+            }
+        }
+
+        ProbeReport {
+            result,
+            previous: ongoing.previous,
+            committed: ongoing.committed,
+            processor_time: ongoing.processor_time,
+            commit_time: ongoing.commit_time,
+        }
+    }
+
+    async fn run_probe(&mut self) -> ProbeResult {
+        // a previous probe already did the processing, so only the commit is left
+        if let Some(pending) = self.pending_commit.take() {
+            self.ongoing.previous = pending.expected.clone();
+            return match self.commit(&pending.expected, &pending.sentinel).await {
+                Ok(CommitOutcome::Committed) => self.acknowledge(&pending.sentinel).await,
+                Ok(CommitOutcome::Conflict) => ProbeResult::Conflict,
+                Err(store_err) => {
+                    self.pending_commit = Some(pending);
+                    ProbeResult::PendingCommit(ProbeError::Store(store_err))
+                }
+            };
+        }
+
         let current_sentinel = match self.store.current().await {
             Ok(current_sentinel) => current_sentinel,
             Err(store_err) => return ProbeResult::Error(ProbeError::Store(store_err)),
         };
+        self.ongoing.previous = current_sentinel.clone();
+
+        let started = Instant::now();
+        let next = self.processor.next(current_sentinel.clone()).await;
+        self.ongoing.processor_time += started.elapsed();
+        let next = match next {
+            Ok(next) => next,
+            Err(proc_err) => return self.on_processor_error(current_sentinel, proc_err).await,
+        };
+        self.retry_hint = next.probe_after;
+        let next_sentinel = match next.sentinel {
+            Some(next_sentinel) => next_sentinel,
+            None => {
+                self.processing_succeeded();
+                return ProbeResult::Empty;
+            }
+        };
+
+        let no_progress = current_sentinel
+            .as_ref()
+            .and_then(|current| self.progress.check(current, &next_sentinel));
+        match (no_progress, self.progress.on_unchanged) {
+            (None, _) => {}
+            (Some(NoProgress::Unchanged), OnUnchanged::Empty) => {
+                self.processing_succeeded();
+                return ProbeResult::Empty;
+            }
+            (Some(NoProgress::Unchanged), OnUnchanged::Warn) => {
+                tracing::warn!(
+                    event = "probe-unchanged",
+                    "the processor returned the sentinel it was given"
+                );
+            }
+            (Some(no_progress), _) => {
+                return self
+                    .on_processor_error(current_sentinel, no_progress.into())
+                    .await;
+            }
+        }
+
+        match self.delivery {
+            DeliveryMode::AtLeastOnce => {
+                if let Err(proc_err) = self.execute(&current_sentinel, &next_sentinel).await {
+                    return self.on_processor_error(current_sentinel, proc_err).await;
+                }
+
+                match self.commit(&current_sentinel, &next_sentinel).await {
+                    Ok(CommitOutcome::Committed) => {}
+                    Ok(CommitOutcome::Conflict) => return ProbeResult::Conflict,
+                    Err(store_err) => {
+                        self.pending_commit = Some(PendingCommit {
+                            expected: current_sentinel,
+                            sentinel: next_sentinel,
+                        });
+                        return ProbeResult::PendingCommit(ProbeError::Store(store_err));
+                    }
+                }
+            }
+            DeliveryMode::AtMostOnce => {
+                // nothing was executed yet, so it's safe to just forget about this sentinel
+                match self.commit(&current_sentinel, &next_sentinel).await {
+                    Ok(CommitOutcome::Committed) => {}
+                    Ok(CommitOutcome::Conflict) => return ProbeResult::Conflict,
+                    Err(store_err) => return ProbeResult::Error(ProbeError::Store(store_err)),
+                }
+
+                if let Err(proc_err) = self.execute(&current_sentinel, &next_sentinel).await {
+                    return ProbeResult::Error(ProbeError::Processor(proc_err));
+                }
+            }
+        }
+
+        self.acknowledge(&next_sentinel).await
+    }
+
+    /// Moves past `current` if the processor failed on it with an [`ErrorClass::Skip`] error, or
+    /// if it's failed on it too many times and should be quarantined, as long as the processor
+    /// knows how to skip it. The error is reported either way.
+    async fn on_processor_error(
+        &mut self,
+        current: Option<Sentinel>,
+        proc_err: DynErr,
+    ) -> ProbeResult {
+        let failures = self.quarantine.as_mut().and_then(|it| it.failed(&current));
+        if failures.is_none() && ErrorClass::of(proc_err.as_ref()) != Some(ErrorClass::Skip) {
+            return ProbeResult::Error(ProbeError::Processor(proc_err));
+        }
 
-        match self.processor.next(current_sentinel).await {
-            Ok(Some(next_sentinel)) => {
-                if let Err(store_err) = self.store.commit(next_sentinel).await {
-                    return ProbeResult::Error(ProbeError::Store(store_err));
+        let started = Instant::now();
+        let skip_to = self.processor.skip(current.as_ref()).await;
+        self.ongoing.processor_time += started.elapsed();
+        let skip_to = skip_to.unwrap_or_else(|skip_err| {
+            tracing::warn!(event = "probe-skip", err = ?skip_err, "couldn't skip sentinel");
+            None
+        });
+
+        if let Some((quarantine, failures)) = self.quarantine.as_mut().zip(failures) {
+            let quarantined = Quarantined {
+                sentinel: current.clone(),
+                skipped_to: skip_to.clone(),
+                error: proc_err.to_string(),
+            };
+            match quarantine.put(quarantined).await {
+                Ok(true) => {
+                    tracing::warn!(event = "probe-quarantine", err = ?proc_err, failures, "quarantined sentinel");
                 }
+                Ok(false) => {}
+                Err(sink_err) => return ProbeResult::Error(ProbeError::Store(sink_err)),
+            }
+        }
 
-                ProbeResult::Success
+        // reporting it as skippable when it wasn't skipped would make it be probed again right
+        // away, forever
+        let Some(skip_to) = skip_to else {
+            return ProbeResult::Error(ProbeError::Processor(ClassifiedError::unskippable(
+                proc_err,
+            )));
+        };
+
+        match self.commit(&current, &skip_to).await {
+            Ok(CommitOutcome::Committed) => {
+                tracing::warn!(event = "probe-skip", err = ?proc_err, "skipped sentinel");
+                ProbeResult::Error(ProbeError::Processor(ClassifiedError::skipped(proc_err)))
             }
-            Ok(None) => ProbeResult::Empty,
+            Ok(CommitOutcome::Conflict) => ProbeResult::Conflict,
+            Err(store_err) => ProbeResult::Error(ProbeError::Store(store_err)),
+        }
+    }
+
+    fn processing_succeeded(&mut self) {
+        if let Some(quarantine) = self.quarantine.as_mut() {
+            quarantine.succeeded();
+        }
+    }
+
+    /// Commits the sentinel, retrying according to the [`CommitRetryPolicy`]. `expected` is only
+    /// checked when conditional commits are enabled.
+    async fn commit(
+        &mut self,
+        expected: &Option<Sentinel>,
+        sentinel: &Sentinel,
+    ) -> Result<CommitOutcome, DynErr> {
+        let started = Instant::now();
+        let result = self.commit_with_retries(expected, sentinel).await;
+        self.ongoing.commit_time += started.elapsed();
+
+        if let Ok(CommitOutcome::Committed) = result {
+            self.processing_succeeded();
+            self.ongoing.committed = Some(sentinel.clone());
+        }
+        result
+    }
+
+    async fn commit_with_retries(
+        &mut self,
+        expected: &Option<Sentinel>,
+        sentinel: &Sentinel,
+    ) -> Result<CommitOutcome, DynErr> {
+        let mut attempt = 0;
+        loop {
+            let result = if self.conditional_commits {
+                self.store
+                    .commit_if(expected.clone(), sentinel.clone())
+                    .await
+            } else {
+                self.store
+                    .commit(sentinel.clone())
+                    .await
+                    .map(|()| CommitOutcome::Committed)
+            };
+
+            match result {
+                Ok(outcome) => return Ok(outcome),
+                Err(store_err) if attempt < self.commit_retry.attempts => {
+                    attempt += 1;
+                    tracing::warn!(event = "commit-error", err = ?store_err, "retrying commit ({attempt}/{})", self.commit_retry.attempts);
+                    RuntimeImpl::sleep(self.commit_retry.delay).await;
+                }
+                Err(store_err) => return Err(store_err),
+            }
+        }
+    }
+
+    async fn execute(&mut self, current: &Option<Sentinel>, next: &Sentinel) -> Result<(), DynErr> {
+        let started = Instant::now();
+        let result = self.processor.execute(current.as_ref(), next).await;
+        self.ongoing.processor_time += started.elapsed();
+        result
+    }
+
+    async fn acknowledge(&mut self, sentinel: &Sentinel) -> ProbeResult {
+        let started = Instant::now();
+        let result = self.processor.acknowledge(sentinel).await;
+        self.ongoing.processor_time += started.elapsed();
+
+        match result {
+            Ok(()) => ProbeResult::Success,
             Err(proc_err) => ProbeResult::Error(ProbeError::Processor(proc_err)),
         }
     }
@@ -62,18 +460,73 @@ pub enum ProbeResult {
     Empty,
     /// The probe returned an error
     Error(ProbeError),
+    /// The processor produced a sentinel, but it couldn't be committed. It's kept so the next
+    /// probe only retries the commit instead of running the processor again.
+    PendingCommit(ProbeError),
+    /// Someone else changed the stored sentinel while probing, so nothing was committed. Only
+    /// happens with conditional commits.
+    Conflict,
 }
 
 impl ProbeResult {
+    pub fn outcome(&self) -> ProbeOutcome {
+        match self {
+            Self::Success => ProbeOutcome::Success,
+            Self::Empty => ProbeOutcome::Empty,
+            Self::Error(_) => ProbeOutcome::Error,
+            Self::PendingCommit(_) => ProbeOutcome::PendingCommit,
+            Self::Conflict => ProbeOutcome::Conflict,
+        }
+    }
+
+    pub fn error(&self) -> Option<&ProbeError> {
+        match self {
+            Self::Error(probe_err) | Self::PendingCommit(probe_err) => Some(probe_err),
+            Self::Success | Self::Empty | Self::Conflict => None,
+        }
+    }
+
     /// Does nothing if it's a [`ProbeResult::Success`] or a [`ProbeResult::Empty`], but panics
-    /// if it's a [`ProbeResult::Error`]
+    /// otherwise
     pub fn expect_ok(self) {
-        if let Self::Error(probe_err) = self {
-            probe_err.panic();
+        match self {
+            Self::Success | Self::Empty => {}
+            Self::Error(probe_err) | Self::PendingCommit(probe_err) => probe_err.panic(),
+            Self::Conflict => panic!("probe conflict: the stored sentinel changed while probing"),
         }
     }
 }
 
+/// A [`ProbeResult`] along with what the probe did
+pub struct ProbeReport<Sentinel> {
+    pub result: ProbeResult,
+    /// The sentinel the probe started from
+    pub previous: Option<Sentinel>,
+    /// The sentinel the probe committed, if it committed one. This isn't always what the
+    /// processor came up with, e.g. when it skipped a sentinel.
+    pub committed: Option<Sentinel>,
+    /// How long was spent in the processor
+    pub processor_time: Duration,
+    /// How long was spent committing, retries included
+    pub commit_time: Duration,
+}
+
+impl<Sentinel> ProbeReport<Sentinel> {
+    pub fn outcome(&self) -> ProbeOutcome {
+        self.result.outcome()
+    }
+}
+
+/// The kind of a [`ProbeResult`], without any of its data
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ProbeOutcome {
+    Success,
+    Empty,
+    Error,
+    PendingCommit,
+    Conflict,
+}
+
 impl From<ProbeError> for ProbeResult {
     fn from(value: ProbeError) -> Self {
         Self::Error(value)
@@ -89,6 +542,13 @@ pub enum ProbeError {
 }
 
 impl ProbeError {
+    /// How the error was classified, if it was
+    pub fn class(&self) -> Option<ErrorClass> {
+        match self {
+            Self::Store(err) | Self::Processor(err) => ErrorClass::of(err.as_ref()),
+        }
+    }
+
     /// Panic with the error message
     pub fn panic(&self) {
         panic!("probe error: {self:?}");
@@ -101,3 +561,373 @@ mockall::mock! {
         pub async fn probe(&mut self) -> ProbeResult;
     }
 }
+
+#[cfg(test)]
+impl<Store, Sentinel, Proc> MockProber<Store, Sentinel, Proc> {
+    pub fn retry_hint(&self) -> Option<Duration> {
+        None
+    }
+
+    pub fn span(&self) -> tracing::Span {
+        tracing::Span::none()
+    }
+
+    pub async fn probe_report(&mut self) -> ProbeReport<Sentinel> {
+        ProbeReport {
+            result: self.probe().await,
+            previous: None,
+            committed: None,
+            processor_time: Duration::ZERO,
+            commit_time: Duration::ZERO,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proc::next::Next;
+    use crate::proc::MockProcessor;
+    use crate::quarantine::mem::MemoryQuarantine;
+    use crate::store::MockSentinelStore;
+
+    #[tokio::test]
+    async fn retries_pending_commit_without_reprocessing() {
+        let mut store = MockSentinelStore::<()>::new();
+        store
+            .expect_current()
+            .times(1)
+            .returning(|| Box::pin(async { Ok(None) }));
+        let mut commit_attempts = 0;
+        store.expect_commit().times(2).returning(move |_| {
+            commit_attempts += 1;
+            let failed = commit_attempts == 1;
+            Box::pin(async move {
+                if failed {
+                    Err("disk full".into())
+                } else {
+                    Ok(())
+                }
+            })
+        });
+
+        let mut processor = MockProcessor::new();
+        processor
+            .expect_next()
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(Some(()).into()) }));
+        processor
+            .expect_execute()
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+        processor
+            .expect_acknowledge()
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let mut prober = Prober::new(store, processor).with_commit_retry(CommitRetryPolicy::none());
+
+        assert!(matches!(
+            prober.probe().await,
+            ProbeResult::PendingCommit(_)
+        ));
+        assert!(prober.has_pending_commit());
+        assert!(matches!(prober.probe().await, ProbeResult::Success));
+        assert!(!prober.has_pending_commit());
+    }
+
+    #[tokio::test]
+    async fn reports_conflict_instead_of_overwriting() {
+        let mut store = MockSentinelStore::<()>::new();
+        store
+            .expect_current()
+            .times(1)
+            .returning(|| Box::pin(async { Ok(None) }));
+        store.expect_commit().never();
+        store
+            .expect_commit_if()
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(CommitOutcome::Conflict) }));
+
+        let mut processor = MockProcessor::new();
+        processor
+            .expect_next()
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(Some(()).into()) }));
+        processor
+            .expect_execute()
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+        processor.expect_acknowledge().never();
+
+        let mut prober = Prober::new(store, processor).with_conditional_commits();
+
+        assert!(matches!(prober.probe().await, ProbeResult::Conflict));
+        assert!(!prober.has_pending_commit());
+    }
+
+    #[tokio::test]
+    async fn commits_past_skipped_sentinel() {
+        let mut store = MockSentinelStore::<()>::new();
+        store
+            .expect_current()
+            .times(1)
+            .returning(|| Box::pin(async { Ok(None) }));
+        store
+            .expect_commit()
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let mut processor = MockProcessor::new();
+        processor
+            .expect_next()
+            .times(1)
+            .returning(|_| Box::pin(async { Err(ClassifiedError::skip("corrupt record").into()) }));
+        processor
+            .expect_skip()
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(Some(())) }));
+        processor.expect_acknowledge().never();
+
+        let mut prober = Prober::new(store, processor);
+
+        let ProbeResult::Error(err) = prober.probe().await else {
+            panic!("should report the error");
+        };
+        assert_eq!(err.class(), Some(ErrorClass::Skip));
+    }
+
+    #[tokio::test]
+    async fn does_not_report_errors_as_skipped_when_they_cannot_be() {
+        let mut prober =
+            Prober::in_memory(crate::proc::FnProcessor::from(|_: Option<u32>| async {
+                Err::<Option<u32>, _>(ClassifiedError::skip("corrupt record").into())
+            }));
+
+        let ProbeResult::Error(err) = prober.probe().await else {
+            panic!("should report the error");
+        };
+        assert_eq!(err.class(), None);
+        assert_eq!(err.to_string(), "processor error: corrupt record");
+    }
+
+    #[tokio::test]
+    async fn quarantines_sentinel_after_repeated_failures() {
+        let mut store = MockSentinelStore::<u32>::new();
+        store
+            .expect_current()
+            .times(2)
+            .returning(|| Box::pin(async { Ok(Some(4)) }));
+        store
+            .expect_commit()
+            .withf(|sentinel| *sentinel == 6)
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let processor = crate::proc::FnProcessor::from(|_| async {
+            Err::<Option<u32>, _>("bad record".into())
+        });
+        let quarantine = MemoryQuarantine::default();
+        let mut prober =
+            Prober::new(store, SkipsTwo(processor)).with_quarantine(2, quarantine.clone());
+
+        let ProbeResult::Error(err) = prober.probe().await else {
+            panic!("should report the error");
+        };
+        assert_eq!(err.class(), None);
+        let ProbeResult::Error(err) = prober.probe().await else {
+            panic!("should report the error");
+        };
+        assert_eq!(err.class(), Some(ErrorClass::Skip));
+
+        assert_eq!(
+            quarantine.quarantined().await.unwrap(),
+            vec![Quarantined {
+                sentinel: Some(4),
+                skipped_to: Some(6),
+                error: "bad record".to_string(),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn quarantines_sentinels_that_cannot_be_skipped_once() {
+        let processor = crate::proc::FnProcessor::from(|_: Option<u32>| async {
+            Err::<Option<u32>, _>("bad record".into())
+        });
+        let quarantine = MemoryQuarantine::default();
+        let mut prober = Prober::in_memory(processor).with_quarantine(2, quarantine.clone());
+
+        for _ in 0..3 {
+            let ProbeResult::Error(err) = prober.probe().await else {
+                panic!("should report the error");
+            };
+            assert_eq!(err.class(), None);
+        }
+
+        assert_eq!(
+            quarantine.quarantined().await.unwrap(),
+            vec![Quarantined {
+                sentinel: None,
+                skipped_to: None,
+                error: "bad record".to_string(),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn counts_failures_per_sentinel() {
+        let mut store = MockSentinelStore::<u32>::new();
+        let mut stored = 3;
+        store.expect_current().times(2).returning(move || {
+            stored += 1;
+            Box::pin(async move { Ok(Some(stored)) })
+        });
+        store.expect_commit().never();
+
+        let processor = crate::proc::FnProcessor::from(|_| async {
+            Err::<Option<u32>, _>("bad record".into())
+        });
+        let quarantine = MemoryQuarantine::default();
+        let mut prober =
+            Prober::new(store, SkipsTwo(processor)).with_quarantine(2, quarantine.clone());
+
+        // someone else moved it along in between
+        prober.probe().await;
+        prober.probe().await;
+
+        assert_eq!(quarantine.quarantined().await.unwrap(), vec![]);
+    }
+
+    /// Skips past the sentinel that comes after the current one
+    struct SkipsTwo<Proc>(Proc);
+
+    #[async_trait::async_trait]
+    impl<Proc: Processor<Sentinel = u32>> Processor for SkipsTwo<Proc> {
+        type Sentinel = u32;
+
+        async fn next(&self, current: Option<u32>) -> Result<Next<u32>, DynErr> {
+            self.0.next(current).await
+        }
+
+        async fn skip(&self, current: Option<&u32>) -> Result<Option<u32>, DynErr> {
+            Ok(current.map(|it| it + 2))
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_where_it_moved() {
+        let mut prober = Prober::in_memory(crate::proc::FnProcessor::from(
+            |current: Option<u32>| async move { Ok(Some(current.unwrap_or(0) + 1)) },
+        ));
+
+        let first = prober.probe_report().await;
+        assert_eq!(first.outcome(), ProbeOutcome::Success);
+        assert_eq!((first.previous, first.committed), (None, Some(1)));
+
+        let second = prober.probe_report().await;
+        assert_eq!((second.previous, second.committed), (Some(1), Some(2)));
+    }
+
+    #[tokio::test]
+    async fn nests_processor_events_in_probe_spans() {
+        // ARRANGE
+        #[derive(Clone, Default)]
+        struct Output(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+        impl std::io::Write for Output {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let output = Output::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer({
+                let output = output.clone();
+                move || output.clone()
+            })
+            .with_ansi(false)
+            .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let mut prober = Prober::in_memory(crate::proc::FnProcessor::from(
+            |current: Option<u32>| async move {
+                tracing::info!("processing");
+                Ok(Some(current.unwrap_or(0) + 1))
+            },
+        ))
+        .with_name("orders")
+        .with_traced_sentinels(|_| "<redacted>".to_string());
+
+        // ACT
+        prober.probe().await.expect_ok();
+        prober.probe().await.expect_ok();
+
+        // ASSERT
+        let output = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].contains(r#"prober{name="orders"}:probe{attempt=1}: "#));
+        assert!(lines[0].ends_with("processing"));
+        assert!(lines[3].contains(
+            r#"probe{attempt=2 outcome=Success previous="<redacted>" committed="<redacted>"}"#
+        ));
+    }
+
+    #[tokio::test]
+    async fn handles_unchanged_sentinels_as_configured() {
+        for (on_unchanged, expected) in [
+            (OnUnchanged::Empty, ProbeOutcome::Empty),
+            (OnUnchanged::Error, ProbeOutcome::Error),
+            (OnUnchanged::Warn, ProbeOutcome::Success),
+        ] {
+            // ARRANGE
+            let mut prober =
+                Prober::in_memory(crate::proc::FnProcessor::from(|_: Option<u32>| async {
+                    Ok(Some(1))
+                }))
+                .with_unchanged_check(on_unchanged);
+            prober.probe().await.expect_ok();
+
+            // ACT
+            let result = prober.probe().await;
+
+            // ASSERT
+            assert_eq!(result.outcome(), expected, "{on_unchanged:?}");
+            if let Some(ProbeError::Processor(err)) = result.error() {
+                assert_eq!(
+                    err.downcast_ref::<NoProgress>(),
+                    Some(&NoProgress::Unchanged)
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_regressions() {
+        // ARRANGE
+        let mut prober = Prober::in_memory(crate::proc::FnProcessor::from(
+            |current: Option<u32>| async move { Ok(Some(current.map_or(5, |it| it - 1))) },
+        ))
+        .with_regression_check();
+        prober.probe().await.expect_ok();
+
+        // ACT
+        let result = prober.probe().await;
+
+        // ASSERT
+        let Some(ProbeError::Processor(err)) = result.error() else {
+            panic!("should be a processor error");
+        };
+        assert_eq!(
+            err.downcast_ref::<NoProgress>(),
+            Some(&NoProgress::Regressed)
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use super::counter::{micros, Counter};
+
+/// Upper bounds of the buckets, in seconds
+pub const BUCKETS: [f64; 11] = [
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 5.0,
+];
+
+/// Counts durations into [`BUCKETS`]
+#[derive(Debug, Default)]
+pub(crate) struct Histogram {
+    /// Not cumulative, unlike the snapshot
+    buckets: [Counter; BUCKETS.len()],
+    count: Counter,
+    sum_micros: Counter,
+}
+
+/// The values of a histogram at a point in time
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HistogramSnapshot {
+    /// How many durations were at most each of [`BUCKETS`], so it doesn't include the ones
+    /// over the last bucket
+    pub buckets: [u64; BUCKETS.len()],
+    pub count: u64,
+    pub sum: Duration,
+}
+
+impl Histogram {
+    pub(crate) fn observe(&self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        if let Some(bucket) = BUCKETS.iter().position(|it| seconds <= *it) {
+            self.buckets[bucket].increment();
+        }
+        self.count.increment();
+        self.sum_micros.add(micros(duration));
+    }
+
+    pub(crate) fn snapshot(&self) -> HistogramSnapshot {
+        let mut buckets = [0; BUCKETS.len()];
+        let mut cumulative = 0;
+        for (snapshot, bucket) in buckets.iter_mut().zip(&self.buckets) {
+            cumulative += bucket.get();
+            *snapshot = cumulative;
+        }
+
+        HistogramSnapshot {
+            buckets,
+            count: self.count.get(),
+            sum: Duration::from_micros(self.sum_micros.get()),
+        }
+    }
+}
@@ -0,0 +1,197 @@
+mod counter;
+pub mod histogram;
+pub mod prometheus;
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+pub(crate) use counter::{micros, Counter};
+pub(crate) use histogram::Histogram;
+
+use crate::{ProbeError, ProbeReport, ProbeResult};
+
+/// Numbers on how an [`AutoProber`](crate::auto::AutoProber) behaves, labelled with the name of
+/// the prober.
+///
+/// Clones share their values, so keep one around to export them, e.g. with a
+/// [`PrometheusExporter`](prometheus::PrometheusExporter).
+#[derive(Clone, Debug)]
+pub struct ProberMetrics {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    name: String,
+    probes: ProbeCounters,
+    processor_latency: Histogram,
+    commit_latency: Histogram,
+    last_commit_at: Mutex<Option<Instant>>,
+    backoff_micros: AtomicU64,
+}
+
+#[derive(Debug, Default)]
+struct ProbeCounters {
+    success: Counter,
+    empty: Counter,
+    store_error: Counter,
+    processor_error: Counter,
+    conflict: Counter,
+    pending_commit: Counter,
+}
+
+/// The values of some [`ProberMetrics`] at a point in time
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProberMetricsSnapshot {
+    pub name: String,
+    /// How many probes came out in each way, keyed by the `outcome` label they're exported with.
+    /// Pending commits are store errors too.
+    pub probes: Vec<(&'static str, u64)>,
+    /// Probes that processed a sentinel but failed to commit it
+    pub pending_commits: u64,
+    pub processor_latency: histogram::HistogramSnapshot,
+    pub commit_latency: histogram::HistogramSnapshot,
+    /// `None` if nothing was committed yet
+    pub since_last_commit: Option<Duration>,
+    /// How long the prober is waiting before the next probe, zero if it isn't
+    pub backoff: Duration,
+}
+
+impl ProberMetrics {
+    pub fn named(name: impl Into<String>) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                name: name.into(),
+                probes: ProbeCounters::default(),
+                processor_latency: Histogram::default(),
+                commit_latency: Histogram::default(),
+                last_commit_at: Mutex::new(None),
+                backoff_micros: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.inner.name
+    }
+
+    pub fn snapshot(&self) -> ProberMetricsSnapshot {
+        let probes = &self.inner.probes;
+        ProberMetricsSnapshot {
+            name: self.inner.name.clone(),
+            probes: vec![
+                ("success", probes.success.get()),
+                ("empty", probes.empty.get()),
+                ("store_error", probes.store_error.get()),
+                ("processor_error", probes.processor_error.get()),
+                ("conflict", probes.conflict.get()),
+            ],
+            pending_commits: probes.pending_commit.get(),
+            processor_latency: self.inner.processor_latency.snapshot(),
+            commit_latency: self.inner.commit_latency.snapshot(),
+            since_last_commit: self
+                .inner
+                .last_commit_at
+                .lock()
+                .unwrap()
+                .map(|it| it.elapsed()),
+            backoff: Duration::from_micros(self.inner.backoff_micros.load(Ordering::Relaxed)),
+        }
+    }
+
+    pub(crate) fn record<Sentinel>(&self, report: &ProbeReport<Sentinel>) {
+        let probes = &self.inner.probes;
+        let counter = match report.result {
+            ProbeResult::Success => &probes.success,
+            ProbeResult::Empty => &probes.empty,
+            ProbeResult::Error(ProbeError::Store(_)) => &probes.store_error,
+            ProbeResult::Error(ProbeError::Processor(_)) => &probes.processor_error,
+            ProbeResult::PendingCommit(_) => {
+                probes.pending_commit.increment();
+                &probes.store_error
+            }
+            ProbeResult::Conflict => &probes.conflict,
+        };
+        counter.increment();
+
+        // a probe that didn't get as far as the processor or the commit took no time in them
+        if !report.processor_time.is_zero() {
+            self.inner.processor_latency.observe(report.processor_time);
+        }
+        if !report.commit_time.is_zero() {
+            self.inner.commit_latency.observe(report.commit_time);
+        }
+        if report.committed.is_some() {
+            *self.inner.last_commit_at.lock().unwrap() = Some(Instant::now());
+        }
+    }
+
+    pub(crate) fn set_backoff(&self, delay: Duration) {
+        self.inner
+            .backoff_micros
+            .store(micros(delay), Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(result: ProbeResult, committed: Option<u32>) -> ProbeReport<u32> {
+        ProbeReport {
+            result,
+            previous: None,
+            committed,
+            processor_time: Duration::from_millis(3),
+            commit_time: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn counts_errors_by_where_they_came_from() {
+        // ARRANGE
+        let metrics = ProberMetrics::named("orders");
+
+        // ACT
+        metrics.record(&report(ProbeResult::Success, Some(1)));
+        metrics.record(&report(
+            ProbeResult::Error(ProbeError::Store("down".into())),
+            None,
+        ));
+        metrics.record(&report(
+            ProbeResult::Error(ProbeError::Processor("bad".into())),
+            None,
+        ));
+        metrics.record(&report(
+            ProbeResult::Error(ProbeError::Processor("bad".into())),
+            None,
+        ));
+        metrics.record(&report(
+            ProbeResult::PendingCommit(ProbeError::Store("down".into())),
+            None,
+        ));
+
+        // ASSERT
+        let snapshot = metrics.snapshot();
+        let count = |outcome| {
+            snapshot
+                .probes
+                .iter()
+                .find(|(it, _)| *it == outcome)
+                .unwrap()
+                .1
+        };
+        assert_eq!(count("success"), 1);
+        assert_eq!(count("store_error"), 2);
+        assert_eq!(count("processor_error"), 2);
+        assert_eq!(snapshot.pending_commits, 1);
+        assert_eq!(snapshot.processor_latency.count, 5);
+        assert_eq!(snapshot.commit_latency.count, 0);
+        assert!(snapshot.since_last_commit.is_some());
+    }
+}
@@ -0,0 +1,266 @@
+use std::{
+    fmt::Write as _,
+    sync::{Arc, Mutex},
+};
+
+use super::{
+    histogram::{HistogramSnapshot, BUCKETS},
+    ProberMetrics, ProberMetricsSnapshot,
+};
+
+/// Renders the [`ProberMetrics`] of any number of probers in the Prometheus text format, to be
+/// served from wherever the metrics are scraped.
+///
+/// Clones share their probers, so it can be registered to from one place and rendered from
+/// another.
+#[derive(Clone, Debug, Default)]
+pub struct PrometheusExporter {
+    probers: Arc<Mutex<Vec<ProberMetrics>>>,
+}
+
+impl PrometheusExporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&self, metrics: &ProberMetrics) {
+        self.probers.lock().unwrap().push(metrics.clone());
+    }
+
+    /// The content type to serve [`PrometheusExporter::render`] with
+    pub fn content_type(&self) -> &'static str {
+        "text/plain; version=0.0.4"
+    }
+
+    pub fn render(&self) -> String {
+        let snapshots = self
+            .probers
+            .lock()
+            .unwrap()
+            .iter()
+            .map(ProberMetrics::snapshot)
+            .collect::<Vec<_>>();
+
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "mrprober_probes_total",
+            "counter",
+            "Probes run, by outcome",
+        );
+        for snapshot in &snapshots {
+            for (outcome, count) in &snapshot.probes {
+                sample(
+                    &mut out,
+                    "mrprober_probes_total",
+                    snapshot,
+                    &[("outcome", outcome)],
+                    *count as f64,
+                );
+            }
+        }
+
+        header(
+            &mut out,
+            "mrprober_pending_commits_total",
+            "counter",
+            "Probes that processed a sentinel but failed to commit it, also counted as store errors",
+        );
+        for snapshot in &snapshots {
+            sample(
+                &mut out,
+                "mrprober_pending_commits_total",
+                snapshot,
+                &[],
+                snapshot.pending_commits as f64,
+            );
+        }
+
+        header(
+            &mut out,
+            "mrprober_processor_seconds",
+            "histogram",
+            "Time spent in the processor per probe",
+        );
+        for snapshot in &snapshots {
+            histogram(
+                &mut out,
+                "mrprober_processor_seconds",
+                snapshot,
+                &snapshot.processor_latency,
+            );
+        }
+
+        header(
+            &mut out,
+            "mrprober_commit_seconds",
+            "histogram",
+            "Time spent committing per probe, retries included",
+        );
+        for snapshot in &snapshots {
+            histogram(
+                &mut out,
+                "mrprober_commit_seconds",
+                snapshot,
+                &snapshot.commit_latency,
+            );
+        }
+
+        header(
+            &mut out,
+            "mrprober_seconds_since_last_commit",
+            "gauge",
+            "Time since a sentinel was last committed",
+        );
+        for snapshot in &snapshots {
+            // there's no sensible value before the first commit
+            if let Some(since) = snapshot.since_last_commit {
+                sample(
+                    &mut out,
+                    "mrprober_seconds_since_last_commit",
+                    snapshot,
+                    &[],
+                    since.as_secs_f64(),
+                );
+            }
+        }
+
+        header(
+            &mut out,
+            "mrprober_backoff_seconds",
+            "gauge",
+            "How long the prober is waiting before the next probe",
+        );
+        for snapshot in &snapshots {
+            sample(
+                &mut out,
+                "mrprober_backoff_seconds",
+                snapshot,
+                &[],
+                snapshot.backoff.as_secs_f64(),
+            );
+        }
+
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn histogram(
+    out: &mut String,
+    name: &str,
+    snapshot: &ProberMetricsSnapshot,
+    histogram: &HistogramSnapshot,
+) {
+    let bucket_name = format!("{name}_bucket");
+    for (le, count) in BUCKETS.iter().zip(histogram.buckets) {
+        sample(
+            out,
+            &bucket_name,
+            snapshot,
+            &[("le", &le.to_string())],
+            count as f64,
+        );
+    }
+    sample(
+        out,
+        &bucket_name,
+        snapshot,
+        &[("le", "+Inf")],
+        histogram.count as f64,
+    );
+    sample(
+        out,
+        &format!("{name}_sum"),
+        snapshot,
+        &[],
+        histogram.sum.as_secs_f64(),
+    );
+    sample(
+        out,
+        &format!("{name}_count"),
+        snapshot,
+        &[],
+        histogram.count as f64,
+    );
+}
+
+fn sample(
+    out: &mut String,
+    name: &str,
+    snapshot: &ProberMetricsSnapshot,
+    labels: &[(&str, &str)],
+    value: f64,
+) {
+    let _ = write!(out, "{name}{{prober=\"{}\"", escape(&snapshot.name));
+    for (label, label_value) in labels {
+        let _ = write!(out, ",{label}=\"{}\"", escape(label_value));
+    }
+    let _ = writeln!(out, "}} {value}");
+}
+
+fn escape(label_value: &str) -> String {
+    label_value
+        .replace('\\', r"\\")
+        .replace('"', r#"\""#)
+        .replace('\n', r"\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{ProbeReport, ProbeResult};
+
+    #[test]
+    fn renders_every_prober() {
+        // ARRANGE
+        let exporter = PrometheusExporter::new();
+        let orders = ProberMetrics::named("orders");
+        let users = ProberMetrics::named("users \"v2\"");
+        exporter.register(&orders);
+        exporter.register(&users);
+
+        // ACT
+        orders.record(&ProbeReport {
+            result: ProbeResult::Success,
+            previous: None,
+            committed: Some(2),
+            processor_time: Duration::from_millis(20),
+            commit_time: Duration::from_millis(2),
+        });
+        users.set_backoff(Duration::from_millis(1500));
+        let rendered = exporter.render();
+
+        // ASSERT
+        let lines = rendered.lines().collect::<Vec<_>>();
+        assert!(lines.contains(&"# TYPE mrprober_probes_total counter"));
+        assert!(lines.contains(&r#"mrprober_probes_total{prober="orders",outcome="success"} 1"#));
+        assert!(
+            lines.contains(&r#"mrprober_probes_total{prober="users \"v2\"",outcome="success"} 0"#)
+        );
+        assert!(
+            lines.contains(&r#"mrprober_processor_seconds_bucket{prober="orders",le="0.01"} 0"#)
+        );
+        assert!(
+            lines.contains(&r#"mrprober_processor_seconds_bucket{prober="orders",le="0.025"} 1"#)
+        );
+        assert!(
+            lines.contains(&r#"mrprober_processor_seconds_bucket{prober="orders",le="+Inf"} 1"#)
+        );
+        assert!(lines.contains(&r#"mrprober_commit_seconds_count{prober="orders"} 1"#));
+        assert!(lines.contains(&r#"mrprober_backoff_seconds{prober="users \"v2\""} 1.5"#));
+        assert!(lines
+            .iter()
+            .any(|it| it.starts_with(r#"mrprober_seconds_since_last_commit{prober="orders"}"#)));
+        assert!(!lines
+            .iter()
+            .any(|it| it.starts_with(r#"mrprober_seconds_since_last_commit{prober="users"#)));
+    }
+}
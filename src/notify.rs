@@ -0,0 +1,19 @@
+use event_listener::Event;
+
+/// Waits until `condition` holds, checking again whenever `event` is notified
+pub(crate) async fn wait_for(event: &Event, condition: impl Fn() -> bool) {
+    loop {
+        if condition() {
+            return;
+        }
+
+        let listener = event.listen();
+
+        // it might have changed before the listener was registered
+        if condition() {
+            return;
+        }
+
+        listener.await;
+    }
+}
@@ -1,18 +1,32 @@
 //! Preconfigured probers
 
-use crate::{proc::Processor, runtime, store, Prober};
+use std::sync::Arc;
+
+use crate::{proc::Processor, runtime, store, store::keyed::KeyedSentinelStore, Prober};
 
 impl<Sentinel, Proc> Prober<store::mem::MemorySentinelStore<Sentinel>, Sentinel, Proc>
 where
     Sentinel: store::mem::MemoryStorableSentinel,
     Proc: Processor<Sentinel = Sentinel>,
 {
-    /// Creates a new prober that holds its sentinel value in memory
+    /// Creates a new prober that holds its sentinel value in memory. It can't do conditional
+    /// commits, use a [comparable](store::mem::MemorySentinelStore::comparable) store for those.
     pub fn in_memory(processor: Proc) -> Self {
         Self::new(store::mem::MemorySentinelStore::default(), processor)
     }
 }
 
+impl<Sentinel, Proc, Keyed> Prober<store::keyed::NamespacedStore<Keyed>, Sentinel, Proc>
+where
+    Keyed: KeyedSentinelStore<Sentinel>,
+    Proc: Processor<Sentinel = Sentinel>,
+{
+    /// Creates a new prober that keeps its sentinel under `key` in a store shared with others
+    pub fn namespaced(store: &Arc<Keyed>, key: impl Into<String>, processor: Proc) -> Self {
+        Self::new(store.namespace(key), processor)
+    }
+}
+
 #[cfg(feature = "file")]
 impl<Sentinel, Proc> Prober<store::file::FileSentinelStore, Sentinel, Proc>
 where
@@ -0,0 +1,143 @@
+use std::{error::Error, fmt, time::Duration};
+
+use crate::alias::DynErr;
+
+/// What kind of failure a processor error is, so the prober can handle each kind its own way
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// Likely to go away if tried again
+    Transient,
+    /// Won't go away no matter how many times it's tried, e.g. a parse error
+    Permanent,
+    /// The upstream asked to wait this long before trying again
+    RateLimited { retry_after: Duration },
+    /// This sentinel can't ever be processed, so move past it with [`Processor::skip`]
+    ///
+    /// [`Processor::skip`]: super::Processor::skip
+    Skip,
+}
+
+impl ErrorClass {
+    /// The class of the first [`ClassifiedError`] in `err` or any of its sources
+    pub fn of(err: &(dyn Error + 'static)) -> Option<Self> {
+        std::iter::successors(Some(err), |it| (*it).source())
+            .find_map(|it| it.downcast_ref::<ClassifiedError>())
+            .map(|it| it.class)
+    }
+}
+
+/// An error marked with its [`ErrorClass`]. Processors return it in place of the original error.
+#[derive(Debug)]
+pub struct ClassifiedError {
+    pub class: ErrorClass,
+    inner: DynErr,
+}
+
+// it passes for the error it classifies, so it doesn't show up twice in error chains
+impl fmt::Display for ClassifiedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl Error for ClassifiedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.inner.source()
+    }
+}
+
+impl ClassifiedError {
+    pub fn new(class: ErrorClass, source: impl Into<DynErr>) -> Self {
+        Self {
+            class,
+            inner: source.into(),
+        }
+    }
+
+    pub fn transient(source: impl Into<DynErr>) -> Self {
+        Self::new(ErrorClass::Transient, source)
+    }
+
+    pub fn permanent(source: impl Into<DynErr>) -> Self {
+        Self::new(ErrorClass::Permanent, source)
+    }
+
+    pub fn rate_limited(retry_after: Duration, source: impl Into<DynErr>) -> Self {
+        Self::new(ErrorClass::RateLimited { retry_after }, source)
+    }
+
+    pub fn skip(source: impl Into<DynErr>) -> Self {
+        Self::new(ErrorClass::Skip, source)
+    }
+
+    /// Marks `err` as skipped, for when its sentinel was skipped past. If it's classified
+    /// already, its class is replaced instead of classifying it twice.
+    pub(crate) fn skipped(err: DynErr) -> DynErr {
+        match err.downcast::<ClassifiedError>() {
+            Ok(mut classified) => {
+                classified.class = ErrorClass::Skip;
+                classified
+            }
+            Err(err) => Box::new(Self::skip(err)),
+        }
+    }
+
+    /// Takes the class off `err` if it's [`ErrorClass::Skip`], for when it turned out it can't be
+    /// skipped. It's marked as [`ErrorClass::Permanent`] if the class is too deep to take off.
+    pub(crate) fn unskippable(err: DynErr) -> DynErr {
+        if ErrorClass::of(err.as_ref()) != Some(ErrorClass::Skip) {
+            return err;
+        }
+
+        match err.downcast::<ClassifiedError>() {
+            Ok(classified) => classified.inner,
+            Err(err) => Self::permanent(err).into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use thiserror::Error;
+
+    use super::*;
+
+    #[derive(Error, Debug)]
+    #[error("wrapped")]
+    struct Wrapper(#[source] ClassifiedError);
+
+    #[test]
+    fn finds_class_through_sources() {
+        let err: DynErr = Wrapper(ClassifiedError::permanent("bad json")).into();
+
+        assert_eq!(ErrorClass::of(err.as_ref()), Some(ErrorClass::Permanent));
+    }
+
+    #[test]
+    fn unclassified_errors_have_no_class() {
+        let err: DynErr = "who knows".into();
+
+        assert_eq!(ErrorClass::of(err.as_ref()), None);
+    }
+
+    #[test]
+    fn skipping_replaces_the_class() {
+        let err = ClassifiedError::skipped(ClassifiedError::permanent("bad json").into());
+
+        let classified = err.downcast::<ClassifiedError>().unwrap();
+        assert_eq!(classified.class, ErrorClass::Skip);
+        assert!(classified.inner.downcast_ref::<ClassifiedError>().is_none());
+    }
+
+    #[test]
+    fn does_not_repeat_itself_in_error_chains() {
+        let err: DynErr = Wrapper(ClassifiedError::permanent("bad json")).into();
+
+        let chain = std::iter::successors(Some(err.as_ref() as &(dyn Error + 'static)), |it| {
+            (*it).source()
+        })
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+        assert_eq!(chain, ["wrapped", "bad json"]);
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{
+    alias::DynErr,
+    proc::{next::Next, Processor},
+};
+
+use super::Layer;
+
+/// Logs the outcome of every call to the processor through `tracing`
+#[derive(Clone, Debug)]
+pub struct Logging {
+    name: String,
+}
+
+impl Logging {
+    /// Logs with `name` to tell this processor apart from others
+    pub fn named(name: impl Into<String>) -> Self {
+        Self { name: name.into() }
+    }
+}
+
+impl<Proc: Processor> Layer<Proc> for Logging {
+    type Processor = LoggingProcessor<Proc>;
+
+    fn layer(&self, inner: Proc) -> Self::Processor {
+        LoggingProcessor {
+            inner,
+            name: self.name.clone(),
+        }
+    }
+}
+
+pub struct LoggingProcessor<Proc> {
+    inner: Proc,
+    name: String,
+}
+
+#[async_trait::async_trait]
+impl<Proc: Processor> Processor for LoggingProcessor<Proc> {
+    type Sentinel = Proc::Sentinel;
+
+    async fn next(&self, current: Option<Self::Sentinel>) -> Result<Next<Self::Sentinel>, DynErr> {
+        let result = self.inner.next(current).await;
+        match result {
+            Ok(Next {
+                sentinel: Some(_), ..
+            }) => {
+                tracing::debug!(event = "processor-next", processor = self.name, "advanced")
+            }
+            Ok(Next { sentinel: None, .. }) => {
+                tracing::debug!(event = "processor-next", processor = self.name, "empty")
+            }
+            Err(ref err) => {
+                tracing::warn!(event = "processor-next", processor = self.name, err = ?err, "failed")
+            }
+        }
+        result
+    }
+
+    async fn execute(
+        &self,
+        current: Option<&Self::Sentinel>,
+        next: &Self::Sentinel,
+    ) -> Result<(), DynErr> {
+        let result = self.inner.execute(current, next).await;
+        if let Err(ref err) = result {
+            tracing::warn!(event = "processor-execute", processor = self.name, err = ?err, "failed");
+        }
+        result
+    }
+
+    async fn acknowledge(&self, next: &Self::Sentinel) -> Result<(), DynErr> {
+        let result = self.inner.acknowledge(next).await;
+        if let Err(ref err) = result {
+            tracing::warn!(event = "processor-acknowledge", processor = self.name, err = ?err, "failed");
+        }
+        result
+    }
+
+    async fn skip(
+        &self,
+        current: Option<&Self::Sentinel>,
+    ) -> Result<Option<Self::Sentinel>, DynErr> {
+        self.inner.skip(current).await
+    }
+}
@@ -0,0 +1,114 @@
+use std::{sync::Arc, time::Instant};
+
+use crate::{
+    alias::DynErr,
+    metrics::{histogram::HistogramSnapshot, Counter, Histogram},
+    proc::{next::Next, Processor},
+};
+
+use super::Layer;
+
+/// Counts calls to the processor and how long they took.
+///
+/// Clones share their counters, so keep one around to read them with [`Metrics::snapshot`].
+#[derive(Clone, Debug, Default)]
+pub struct Metrics {
+    counters: Arc<Counters>,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    advanced: Counter,
+    empty: Counter,
+    errors: Counter,
+    busy: Histogram,
+}
+
+/// The values of some [`Metrics`] at a point in time
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MetricsSnapshot {
+    /// Calls to [`Processor::next`]
+    pub calls: u64,
+    /// Calls that returned a sentinel
+    pub advanced: u64,
+    /// Calls that returned nothing
+    pub empty: u64,
+    /// Calls that failed
+    pub errors: u64,
+    /// Time spent in [`Processor::next`], per call
+    pub busy: HistogramSnapshot,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let busy = self.counters.busy.snapshot();
+        MetricsSnapshot {
+            calls: busy.count,
+            advanced: self.counters.advanced.get(),
+            empty: self.counters.empty.get(),
+            errors: self.counters.errors.get(),
+            busy,
+        }
+    }
+}
+
+impl<Proc: Processor> Layer<Proc> for Metrics {
+    type Processor = MetricsProcessor<Proc>;
+
+    fn layer(&self, inner: Proc) -> Self::Processor {
+        MetricsProcessor {
+            inner,
+            counters: Arc::clone(&self.counters),
+        }
+    }
+}
+
+pub struct MetricsProcessor<Proc> {
+    inner: Proc,
+    counters: Arc<Counters>,
+}
+
+#[async_trait::async_trait]
+impl<Proc: Processor> Processor for MetricsProcessor<Proc> {
+    type Sentinel = Proc::Sentinel;
+
+    async fn next(&self, current: Option<Self::Sentinel>) -> Result<Next<Self::Sentinel>, DynErr> {
+        let start = Instant::now();
+        let result = self.inner.next(current).await;
+
+        self.counters.busy.observe(start.elapsed());
+        let outcome_counter = match result {
+            Ok(Next {
+                sentinel: Some(_), ..
+            }) => &self.counters.advanced,
+            Ok(Next { sentinel: None, .. }) => &self.counters.empty,
+            Err(_) => &self.counters.errors,
+        };
+        outcome_counter.increment();
+
+        result
+    }
+
+    async fn execute(
+        &self,
+        current: Option<&Self::Sentinel>,
+        next: &Self::Sentinel,
+    ) -> Result<(), DynErr> {
+        self.inner.execute(current, next).await
+    }
+
+    async fn acknowledge(&self, next: &Self::Sentinel) -> Result<(), DynErr> {
+        self.inner.acknowledge(next).await
+    }
+
+    async fn skip(
+        &self,
+        current: Option<&Self::Sentinel>,
+    ) -> Result<Option<Self::Sentinel>, DynErr> {
+        self.inner.skip(current).await
+    }
+}
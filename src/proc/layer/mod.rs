@@ -0,0 +1,64 @@
+//! Middleware that wraps processors with cross-cutting behaviour.
+//!
+//! Layers compose by wrapping each other, the last one applied being the outermost:
+//!
+//! ```ignore
+//! let processor = FnProcessor::from(fetch)
+//!     .layer(Timeout::new(Duration::from_secs(5)))
+//!     .layer(Retry::new(3, Duration::from_millis(500)))
+//!     .layer(Logging::named("feed"));
+//! ```
+
+pub mod logging;
+pub mod metrics;
+pub mod rate_limit;
+pub mod retry;
+pub mod timeout;
+
+pub use logging::Logging;
+pub use metrics::Metrics;
+pub use rate_limit::RateLimit;
+pub use retry::Retry;
+pub use timeout::Timeout;
+
+use super::Processor;
+
+/// Wraps a processor into another one
+pub trait Layer<Proc> {
+    type Processor: Processor;
+
+    fn layer(&self, inner: Proc) -> Self::Processor;
+}
+
+/// Allows wrapping any processor with [`Layer`]s
+pub trait ProcessorExt: Processor + Sized {
+    fn layer<L: Layer<Self>>(self, layer: L) -> L::Processor {
+        layer.layer(self)
+    }
+}
+
+impl<Proc: Processor> ProcessorExt for Proc {}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::proc::FnProcessor;
+
+    #[tokio::test]
+    async fn layers_compose_over_fn_processors() {
+        let metrics = Metrics::new();
+        let proc =
+            FnProcessor::from(
+                |current: Option<u32>| async move { Ok(Some(current.unwrap_or(0) + 1)) },
+            )
+            .layer(Timeout::new(Duration::from_secs(1)))
+            .layer(Retry::new(2, Duration::ZERO))
+            .layer(metrics.clone())
+            .layer(Logging::named("test"));
+
+        assert_eq!(proc.next(Some(41)).await.unwrap().sentinel, Some(42));
+        assert_eq!(metrics.snapshot().calls, 1);
+    }
+}
@@ -0,0 +1,141 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    alias::DynErr,
+    proc::{next::Next, Processor},
+    runtime::{Runtime as _, RuntimeImpl},
+};
+
+use super::Layer;
+
+/// Allows at most a number of calls to the processor within each window of time, waiting for the
+/// next window when they run out.
+///
+/// Layers created from the same [`RateLimit`] share its limit.
+#[derive(Clone, Debug)]
+pub struct RateLimit {
+    calls: u32,
+    per: Duration,
+    window: Arc<Mutex<Window>>,
+}
+
+#[derive(Debug)]
+struct Window {
+    started_at: Instant,
+    calls: u32,
+}
+
+impl RateLimit {
+    /// Allows `calls` calls every `per`
+    ///
+    /// # Panics
+    ///
+    /// If `calls` is `0`, which would never allow any
+    pub fn new(calls: u32, per: Duration) -> Self {
+        assert!(calls > 0, "a rate limit must allow some calls");
+        Self {
+            calls,
+            per,
+            window: Arc::new(Mutex::new(Window {
+                started_at: Instant::now(),
+                calls: 0,
+            })),
+        }
+    }
+
+    /// Waits until there's a call available within the limit, and takes it
+    async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut window = self.window.lock().unwrap();
+                let elapsed = window.started_at.elapsed();
+                if elapsed >= self.per {
+                    window.started_at = Instant::now();
+                    window.calls = 0;
+                }
+
+                if window.calls < self.calls {
+                    window.calls += 1;
+                    return;
+                }
+
+                self.per.saturating_sub(elapsed)
+            };
+
+            RuntimeImpl::sleep(wait).await;
+        }
+    }
+}
+
+impl<Proc: Processor> Layer<Proc> for RateLimit {
+    type Processor = RateLimitProcessor<Proc>;
+
+    fn layer(&self, inner: Proc) -> Self::Processor {
+        RateLimitProcessor {
+            inner,
+            limit: self.clone(),
+        }
+    }
+}
+
+pub struct RateLimitProcessor<Proc> {
+    inner: Proc,
+    limit: RateLimit,
+}
+
+#[async_trait::async_trait]
+impl<Proc: Processor> Processor for RateLimitProcessor<Proc> {
+    type Sentinel = Proc::Sentinel;
+
+    async fn next(&self, current: Option<Self::Sentinel>) -> Result<Next<Self::Sentinel>, DynErr> {
+        self.limit.acquire().await;
+        self.inner.next(current).await
+    }
+
+    async fn execute(
+        &self,
+        current: Option<&Self::Sentinel>,
+        next: &Self::Sentinel,
+    ) -> Result<(), DynErr> {
+        self.inner.execute(current, next).await
+    }
+
+    async fn acknowledge(&self, next: &Self::Sentinel) -> Result<(), DynErr> {
+        self.inner.acknowledge(next).await
+    }
+
+    async fn skip(
+        &self,
+        current: Option<&Self::Sentinel>,
+    ) -> Result<Option<Self::Sentinel>, DynErr> {
+        self.inner.skip(current).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proc::{layer::ProcessorExt as _, FnProcessor};
+
+    #[tokio::test]
+    async fn waits_for_next_window_when_exhausted() {
+        let proc = FnProcessor::from(|_: Option<u32>| async { Ok(Some(1)) })
+            .layer(RateLimit::new(2, Duration::from_millis(200)));
+
+        let start = Instant::now();
+        for _ in 0..3 {
+            proc.next(None).await.unwrap();
+        }
+
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[test]
+    #[should_panic(expected = "some calls")]
+    fn rejects_limits_without_calls() {
+        RateLimit::new(0, Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,174 @@
+use std::time::Duration;
+
+use crate::{
+    alias::DynErr,
+    proc::{error::ErrorClass, next::Next, Processor},
+    runtime::{Runtime as _, RuntimeImpl},
+};
+
+use super::Layer;
+
+/// Retries failed processing right away, before the prober ever sees the error. Permanent and
+/// skip errors aren't retried, and rate limited ones wait at least as long as they asked.
+#[derive(Clone, Debug)]
+pub struct Retry {
+    attempts: u32,
+    delay: Duration,
+}
+
+impl Retry {
+    /// Retries up to `attempts` times, waiting `delay` in between
+    pub fn new(attempts: u32, delay: Duration) -> Self {
+        Self { attempts, delay }
+    }
+}
+
+impl<Proc> Layer<Proc> for Retry
+where
+    Proc: Processor,
+    Proc::Sentinel: Clone,
+{
+    type Processor = RetryProcessor<Proc>;
+
+    fn layer(&self, inner: Proc) -> Self::Processor {
+        RetryProcessor {
+            inner,
+            retry: self.clone(),
+        }
+    }
+}
+
+pub struct RetryProcessor<Proc> {
+    inner: Proc,
+    retry: Retry,
+}
+
+#[async_trait::async_trait]
+impl<Proc> Processor for RetryProcessor<Proc>
+where
+    Proc: Processor,
+    Proc::Sentinel: Clone,
+{
+    type Sentinel = Proc::Sentinel;
+
+    async fn next(&self, current: Option<Self::Sentinel>) -> Result<Next<Self::Sentinel>, DynErr> {
+        let mut attempt = 0;
+        loop {
+            let result = self.inner.next(current.clone()).await;
+            let delay = result
+                .as_ref()
+                .err()
+                .filter(|_| attempt < self.retry.attempts)
+                .and_then(|err| self.retry.delay_for(err.as_ref()));
+            let Some(delay) = delay else {
+                return result;
+            };
+
+            attempt += 1;
+            tracing::warn!(event = "processor-retry", err = ?result.err(), "retrying ({attempt}/{})", self.retry.attempts);
+            RuntimeImpl::sleep(delay).await;
+        }
+    }
+
+    async fn execute(
+        &self,
+        current: Option<&Self::Sentinel>,
+        next: &Self::Sentinel,
+    ) -> Result<(), DynErr> {
+        self.inner.execute(current, next).await
+    }
+
+    async fn acknowledge(&self, next: &Self::Sentinel) -> Result<(), DynErr> {
+        self.inner.acknowledge(next).await
+    }
+
+    async fn skip(
+        &self,
+        current: Option<&Self::Sentinel>,
+    ) -> Result<Option<Self::Sentinel>, DynErr> {
+        self.inner.skip(current).await
+    }
+}
+
+impl Retry {
+    /// How long to wait before trying again, or `None` if it could never make a difference
+    fn delay_for(&self, err: &(dyn std::error::Error + 'static)) -> Option<Duration> {
+        match ErrorClass::of(err) {
+            Some(ErrorClass::Permanent | ErrorClass::Skip) => None,
+            Some(ErrorClass::RateLimited { retry_after }) => Some(self.delay.max(retry_after)),
+            Some(ErrorClass::Transient) | None => Some(self.delay),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    };
+
+    use super::*;
+    use crate::proc::{error::ClassifiedError, layer::ProcessorExt as _, FnProcessor};
+
+    #[tokio::test]
+    async fn retries_until_success() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let proc = FnProcessor::from({
+            let calls = Arc::clone(&calls);
+            move |_: Option<u32>| {
+                let calls = Arc::clone(&calls);
+                async move {
+                    if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                        Err("flaky".into())
+                    } else {
+                        Ok(Some(1))
+                    }
+                }
+            }
+        })
+        .layer(Retry::new(2, Duration::ZERO));
+
+        assert_eq!(proc.next(None).await.unwrap().sentinel, Some(1));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_on_permanent_errors() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let proc = FnProcessor::from({
+            let calls = Arc::clone(&calls);
+            move |_: Option<u32>| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async { Err::<Option<u32>, _>(ClassifiedError::permanent("bad json").into()) }
+            }
+        })
+        .layer(Retry::new(2, Duration::ZERO));
+
+        assert!(proc.next(None).await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn waits_as_long_as_rate_limited_errors_ask() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let proc = FnProcessor::from({
+            let calls = Arc::clone(&calls);
+            move |_: Option<u32>| {
+                let calls = Arc::clone(&calls);
+                async move {
+                    if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                        Err(ClassifiedError::rate_limited(Duration::from_millis(50), "429").into())
+                    } else {
+                        Ok(Some(1))
+                    }
+                }
+            }
+        })
+        .layer(Retry::new(2, Duration::ZERO));
+
+        let started = std::time::Instant::now();
+        assert_eq!(proc.next(None).await.unwrap().sentinel, Some(1));
+        assert!(started.elapsed() >= Duration::from_millis(50));
+    }
+}
@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use thiserror::Error;
+
+use crate::{
+    alias::DynErr,
+    proc::{next::Next, Processor},
+    runtime::{Runtime as _, RuntimeImpl},
+};
+
+use super::Layer;
+
+/// Fails processing that takes longer than a given duration
+#[derive(Clone, Debug)]
+pub struct Timeout {
+    duration: Duration,
+}
+
+impl Timeout {
+    pub fn new(duration: Duration) -> Self {
+        Self { duration }
+    }
+}
+
+impl<Proc: Processor> Layer<Proc> for Timeout {
+    type Processor = TimeoutProcessor<Proc>;
+
+    fn layer(&self, inner: Proc) -> Self::Processor {
+        TimeoutProcessor {
+            inner,
+            duration: self.duration,
+        }
+    }
+}
+
+pub struct TimeoutProcessor<Proc> {
+    inner: Proc,
+    duration: Duration,
+}
+
+#[derive(Error, Debug)]
+#[error("processor timed out after {0:?}")]
+pub struct TimeoutElapsed(pub Duration);
+
+#[async_trait::async_trait]
+impl<Proc: Processor> Processor for TimeoutProcessor<Proc> {
+    type Sentinel = Proc::Sentinel;
+
+    async fn next(&self, current: Option<Self::Sentinel>) -> Result<Next<Self::Sentinel>, DynErr> {
+        RuntimeImpl::timeout(self.duration, self.inner.next(current))
+            .await
+            .unwrap_or_else(|| Err(TimeoutElapsed(self.duration).into()))
+    }
+
+    async fn execute(
+        &self,
+        current: Option<&Self::Sentinel>,
+        next: &Self::Sentinel,
+    ) -> Result<(), DynErr> {
+        RuntimeImpl::timeout(self.duration, self.inner.execute(current, next))
+            .await
+            .unwrap_or_else(|| Err(TimeoutElapsed(self.duration).into()))
+    }
+
+    async fn acknowledge(&self, next: &Self::Sentinel) -> Result<(), DynErr> {
+        self.inner.acknowledge(next).await
+    }
+
+    async fn skip(
+        &self,
+        current: Option<&Self::Sentinel>,
+    ) -> Result<Option<Self::Sentinel>, DynErr> {
+        self.inner.skip(current).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proc::{layer::ProcessorExt as _, FnProcessor};
+
+    #[tokio::test]
+    async fn times_out_slow_processors() {
+        let proc = FnProcessor::from(|_: Option<u32>| async {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            Ok(Some(1))
+        })
+        .layer(Timeout::new(Duration::from_millis(10)));
+
+        let err = proc.next(None).await.unwrap_err();
+        assert!(err.is::<TimeoutElapsed>());
+    }
+}
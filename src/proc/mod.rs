@@ -0,0 +1,97 @@
+pub mod error;
+pub mod layer;
+pub mod next;
+
+use std::{future::Future, marker::PhantomData};
+
+use crate::alias::DynErr;
+
+use next::Next;
+
+#[async_trait::async_trait]
+#[cfg_attr(test, mockall::automock(type Sentinel = ();))]
+pub trait Processor: Send + Sync {
+    type Sentinel: Send + Sync;
+
+    /// Plans the sentinel that follows `current`, or returns an empty [`Next`] if there's nothing
+    /// new. It can also suggest when to probe again.
+    ///
+    /// Single-phase processors do all of their work here. Two-phase processors should only work
+    /// out the next sentinel and leave the side effects to [`Processor::execute`].
+    async fn next(&self, current: Option<Self::Sentinel>) -> Result<Next<Self::Sentinel>, DynErr>;
+
+    /// Carries out the side effects of advancing from `current` to `next`. When it's called
+    /// relative to the commit depends on the [`DeliveryMode`](crate::delivery::DeliveryMode).
+    async fn execute(
+        &self,
+        _current: Option<&Self::Sentinel>,
+        _next: &Self::Sentinel,
+    ) -> Result<(), DynErr> {
+        Ok(())
+    }
+
+    /// Called once `next` has been both executed and committed
+    async fn acknowledge(&self, _next: &Self::Sentinel) -> Result<(), DynErr> {
+        Ok(())
+    }
+
+    /// The sentinel right past `current`, for when processing it failed with an
+    /// [`ErrorClass::Skip`](error::ErrorClass::Skip) error. `None` if it can't be skipped.
+    async fn skip(
+        &self,
+        _current: Option<&Self::Sentinel>,
+    ) -> Result<Option<Self::Sentinel>, DynErr> {
+        Ok(None)
+    }
+}
+
+pub struct FnProcessor<F, Sentinel> {
+    f: F,
+    _sentinel: PhantomData<Sentinel>,
+}
+
+// TODO, FUTURE: I think async closures would be great to clean the bounds
+/// The closure can return either an `Option` of the next sentinel or a whole [`Next`]
+impl<F, Fut, Out, Sentinel> From<F> for FnProcessor<F, Sentinel>
+where
+    F: Fn(Option<Sentinel>) -> Fut,
+    Fut: Future<Output = Result<Out, DynErr>>,
+    Out: Into<Next<Sentinel>>,
+{
+    fn from(value: F) -> Self {
+        FnProcessor {
+            f: value,
+            _sentinel: PhantomData,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<F, Fut, Out, Sentinel> Processor for FnProcessor<F, Sentinel>
+where
+    Sentinel: Send + Sync + 'static,
+    F: Fn(Option<Sentinel>) -> Fut + Send + Sync,
+    Fut: Future<Output = Result<Out, DynErr>> + Send,
+    Out: Into<Next<Sentinel>>,
+{
+    type Sentinel = Sentinel;
+
+    async fn next(&self, current: Option<Self::Sentinel>) -> Result<Next<Self::Sentinel>, DynErr> {
+        (self.f)(current).await.map(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn fn_processor_works() {
+        let proc = FnProcessor::from(|_| async { Ok(Some(42)) });
+
+        assert_eq!(
+            proc.next(None).await.expect("should be ok").sentinel,
+            Some(42)
+        );
+    }
+}
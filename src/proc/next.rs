@@ -0,0 +1,50 @@
+use std::time::{Duration, SystemTime};
+
+/// What a [`Processor`](super::Processor) came up with after the current sentinel
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Next<Sentinel> {
+    /// The sentinel to advance to, or `None` if there's nothing new
+    pub sentinel: Option<Sentinel>,
+    /// How long to wait before probing again, if the processor knows better than the prober's
+    /// strategy, e.g. from a `Retry-After` header. It's only followed when there's nothing new.
+    pub probe_after: Option<Duration>,
+}
+
+impl<Sentinel> Next<Sentinel> {
+    pub fn advance(sentinel: Sentinel) -> Self {
+        Self {
+            sentinel: Some(sentinel),
+            probe_after: None,
+        }
+    }
+
+    pub fn empty() -> Self {
+        Self {
+            sentinel: None,
+            probe_after: None,
+        }
+    }
+
+    /// Suggest waiting `delay` before the next probe
+    pub fn with_probe_after(mut self, delay: Duration) -> Self {
+        self.probe_after = Some(delay);
+        self
+    }
+
+    /// Suggest not probing again until `at`
+    pub fn with_probe_at(self, at: SystemTime) -> Self {
+        self.with_probe_after(
+            at.duration_since(SystemTime::now())
+                .unwrap_or(Duration::ZERO),
+        )
+    }
+}
+
+impl<Sentinel> From<Option<Sentinel>> for Next<Sentinel> {
+    fn from(sentinel: Option<Sentinel>) -> Self {
+        Self {
+            sentinel,
+            probe_after: None,
+        }
+    }
+}
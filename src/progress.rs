@@ -0,0 +1,61 @@
+use thiserror::Error;
+
+/// What a probe comes out as when the processor returns the very sentinel it was given, see
+/// [`Prober::with_unchanged_check`](crate::Prober::with_unchanged_check)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OnUnchanged {
+    /// As if there was nothing new
+    #[default]
+    Empty,
+    /// As a processor error, so it's handled like any other
+    Error,
+    /// Commit it anyway, but log a warning
+    Warn,
+}
+
+/// Why a sentinel the processor returned was rejected
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum NoProgress {
+    #[error("the processor returned the sentinel it was given")]
+    Unchanged,
+    #[error("the processor returned a sentinel that comes before the one it was given")]
+    Regressed,
+}
+
+type Comparison<Sentinel> = Box<dyn Fn(&Sentinel, &Sentinel) -> bool + Send + Sync>;
+
+/// Checks that the processor moves sentinels forward
+pub(crate) struct ProgressChecks<Sentinel> {
+    /// Whether the sentinels are the same
+    pub(crate) unchanged: Option<Comparison<Sentinel>>,
+    pub(crate) on_unchanged: OnUnchanged,
+    /// Whether the second sentinel comes before the first
+    pub(crate) regressed: Option<Comparison<Sentinel>>,
+}
+
+impl<Sentinel> Default for ProgressChecks<Sentinel> {
+    fn default() -> Self {
+        Self {
+            unchanged: None,
+            on_unchanged: OnUnchanged::default(),
+            regressed: None,
+        }
+    }
+}
+
+impl<Sentinel> ProgressChecks<Sentinel> {
+    /// What's wrong with moving from `current` to `next`, if anything
+    pub(crate) fn check(&self, current: &Sentinel, next: &Sentinel) -> Option<NoProgress> {
+        let fails = |check: &Option<Comparison<Sentinel>>| {
+            check.as_ref().is_some_and(|check| check(current, next))
+        };
+
+        if fails(&self.unchanged) {
+            Some(NoProgress::Unchanged)
+        } else if fails(&self.regressed) {
+            Some(NoProgress::Regressed)
+        } else {
+            None
+        }
+    }
+}
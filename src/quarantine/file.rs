@@ -0,0 +1,141 @@
+use serde_json::{json, Value};
+
+use crate::{
+    alias::DynErr,
+    runtime::{Runtime, RuntimeImpl},
+    store::file::FileStorableSentinel,
+};
+
+use super::{QuarantineSink, Quarantined};
+
+/// Keeps quarantined sentinels in a JSON file, as an array of objects with `sentinel`,
+/// `skipped_to` and `error` fields.
+///
+/// Sentinels are saved with [`ToString`] and retrieved with [`FromStr`](std::str::FromStr), just
+/// like in a [`FileSentinelStore`](crate::store::file::FileSentinelStore).
+pub struct FileQuarantine {
+    file: <RuntimeImpl as Runtime>::File,
+}
+
+impl FileQuarantine {
+    pub async fn open(file_path: &str) -> Result<Self, <RuntimeImpl as Runtime>::Err> {
+        Ok(Self {
+            file: RuntimeImpl::open_file(file_path).await?,
+        })
+    }
+
+    async fn read(&self) -> Result<(String, Vec<Value>), DynErr> {
+        let contents = RuntimeImpl::read_string(&self.file).await?;
+        let entries = if contents.trim().is_empty() {
+            Vec::new()
+        } else {
+            serde_json::from_str(&contents)?
+        };
+
+        Ok((contents, entries))
+    }
+}
+
+#[async_trait::async_trait]
+impl<Sentinel: FileStorableSentinel> QuarantineSink<Sentinel> for FileQuarantine {
+    async fn quarantine(&mut self, quarantined: Quarantined<Sentinel>) -> Result<(), DynErr> {
+        let entry = json!({
+            "sentinel": quarantined.sentinel.map(|it| it.to_string()),
+            "skipped_to": quarantined.skipped_to.map(|it| it.to_string()),
+            "error": quarantined.error,
+        });
+
+        // starting over if another process quarantined something in the meantime
+        loop {
+            let (contents, mut entries) = self.read().await?;
+            entries.push(entry.clone());
+
+            let new_contents = serde_json::to_string(&entries)?;
+            if RuntimeImpl::compare_and_write(&self.file, &contents, &new_contents).await? {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn quarantined(&self) -> Result<Vec<Quarantined<Sentinel>>, DynErr> {
+        let (_, entries) = self.read().await?;
+        entries
+            .iter()
+            .map(|entry| {
+                let field = |name| entry[name].as_str();
+                Ok(Quarantined {
+                    sentinel: field("sentinel").map(Sentinel::from_str).transpose()?,
+                    skipped_to: field("skipped_to").map(Sentinel::from_str).transpose()?,
+                    error: field("error").unwrap_or_default().to_string(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::distributions::DistString as _;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn reads_back_what_was_quarantined() {
+        let test_id = rand::distributions::Alphanumeric.sample_string(&mut rand::thread_rng(), 10);
+        let file_path = format!("/tmp/mrprober-test-{test_id}");
+        let mut quarantine = FileQuarantine::open(&file_path).await.unwrap();
+
+        let quarantined = vec![
+            Quarantined {
+                sentinel: None,
+                skipped_to: None,
+                error: "bad json".to_string(),
+            },
+            Quarantined {
+                sentinel: Some(5),
+                skipped_to: Some(6),
+                error: "bad json".to_string(),
+            },
+        ];
+        for entry in quarantined.clone() {
+            quarantine.quarantine(entry).await.unwrap();
+        }
+
+        assert_eq!(
+            QuarantineSink::<u32>::quarantined(&quarantine)
+                .await
+                .unwrap(),
+            quarantined
+        );
+    }
+
+    #[tokio::test]
+    async fn keeps_what_several_processes_quarantined_at_once() {
+        let test_id = rand::distributions::Alphanumeric.sample_string(&mut rand::thread_rng(), 10);
+        let file_path = format!("/tmp/mrprober-test-{test_id}");
+        let mut first = FileQuarantine::open(&file_path).await.unwrap();
+        let mut second = FileQuarantine::open(&file_path).await.unwrap();
+
+        let quarantined = |sentinel| Quarantined {
+            sentinel: Some(sentinel),
+            skipped_to: None,
+            error: "bad json".to_string(),
+        };
+        let (first_result, second_result) = futures_lite::future::zip(
+            first.quarantine(quarantined(1_u32)),
+            second.quarantine(quarantined(2_u32)),
+        )
+        .await;
+        first_result.unwrap();
+        second_result.unwrap();
+
+        let mut sentinels = QuarantineSink::<u32>::quarantined(&first)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|it| it.sentinel)
+            .collect::<Vec<_>>();
+        sentinels.sort();
+        assert_eq!(sentinels, vec![Some(1), Some(2)]);
+    }
+}
@@ -0,0 +1,32 @@
+use std::sync::{Arc, Mutex};
+
+use crate::alias::DynErr;
+
+use super::{QuarantineSink, Quarantined};
+
+/// Keeps quarantined sentinels in memory. Clones share the same sentinels, so keep one around to
+/// look at them.
+#[derive(Clone)]
+pub struct MemoryQuarantine<Sentinel> {
+    quarantined: Arc<Mutex<Vec<Quarantined<Sentinel>>>>,
+}
+
+impl<Sentinel> Default for MemoryQuarantine<Sentinel> {
+    fn default() -> Self {
+        Self {
+            quarantined: Arc::default(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<Sentinel: Clone + Send + 'static> QuarantineSink<Sentinel> for MemoryQuarantine<Sentinel> {
+    async fn quarantine(&mut self, quarantined: Quarantined<Sentinel>) -> Result<(), DynErr> {
+        self.quarantined.lock().unwrap().push(quarantined);
+        Ok(())
+    }
+
+    async fn quarantined(&self) -> Result<Vec<Quarantined<Sentinel>>, DynErr> {
+        Ok(self.quarantined.lock().unwrap().clone())
+    }
+}
@@ -0,0 +1,99 @@
+#[cfg(feature = "file")]
+pub mod file;
+pub mod mem;
+
+use crate::alias::DynErr;
+
+/// Where a [`Prober`](crate::Prober) puts the sentinels processing kept failing on, so they can
+/// be looked into later
+#[async_trait::async_trait]
+pub trait QuarantineSink<Sentinel>: Send {
+    async fn quarantine(&mut self, quarantined: Quarantined<Sentinel>) -> Result<(), DynErr>;
+
+    /// Everything quarantined so far, oldest first
+    async fn quarantined(&self) -> Result<Vec<Quarantined<Sentinel>>, DynErr>;
+}
+
+/// A sentinel that processing kept failing on
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Quarantined<Sentinel> {
+    /// Where the prober was stuck. `None` if it hadn't committed anything yet.
+    pub sentinel: Option<Sentinel>,
+    /// What the processor said to skip to. `None` if it couldn't skip it, so the prober is still
+    /// stuck on it.
+    pub skipped_to: Option<Sentinel>,
+    /// The last error processing it
+    pub error: String,
+}
+
+type Comparison<Sentinel> = Box<dyn Fn(&Sentinel, &Sentinel) -> bool + Send + Sync>;
+
+/// When to quarantine sentinels and where to put them
+pub(crate) struct Quarantine<Sentinel> {
+    after_failures: u32,
+    sink: Box<dyn QuarantineSink<Sentinel>>,
+    same: Comparison<Sentinel>,
+    /// The sentinel processing is failing on, if it is
+    failing: Option<Failing<Sentinel>>,
+}
+
+struct Failing<Sentinel> {
+    sentinel: Option<Sentinel>,
+    /// How many times in a row
+    failures: u32,
+    /// Whether it's in the sink already
+    quarantined: bool,
+}
+
+impl<Sentinel: PartialEq + 'static> Quarantine<Sentinel> {
+    pub(crate) fn new(after_failures: u32, sink: impl QuarantineSink<Sentinel> + 'static) -> Self {
+        Self {
+            after_failures,
+            sink: Box::new(sink),
+            same: Box::new(|a, b| a == b),
+            failing: None,
+        }
+    }
+}
+
+impl<Sentinel: Clone> Quarantine<Sentinel> {
+    /// Counts another failure on `sentinel`, starting over if it used to fail on another one.
+    /// Returns how many times in a row it failed, if that's enough to quarantine it.
+    pub(crate) fn failed(&mut self, sentinel: &Option<Sentinel>) -> Option<u32> {
+        let same = |failing: &Failing<Sentinel>| match (&failing.sentinel, sentinel) {
+            (Some(a), Some(b)) => (self.same)(a, b),
+            (a, b) => a.is_none() && b.is_none(),
+        };
+        match self.failing.as_mut().filter(|it| same(it)) {
+            Some(failing) => failing.failures += 1,
+            None => {
+                self.failing = Some(Failing {
+                    sentinel: sentinel.clone(),
+                    failures: 1,
+                    quarantined: false,
+                })
+            }
+        }
+
+        let failures = self.failing.as_ref().map_or(0, |it| it.failures);
+        (failures >= self.after_failures).then_some(failures)
+    }
+
+    /// Processing didn't fail this time
+    pub(crate) fn succeeded(&mut self) {
+        self.failing = None;
+    }
+
+    /// Puts the failing sentinel into the sink, unless it's there already. Returns whether it
+    /// wasn't.
+    pub(crate) async fn put(&mut self, quarantined: Quarantined<Sentinel>) -> Result<bool, DynErr> {
+        if self.failing.as_ref().is_some_and(|it| it.quarantined) {
+            return Ok(false);
+        }
+        self.sink.quarantine(quarantined).await?;
+        if let Some(failing) = self.failing.as_mut() {
+            failing.quarantined = true;
+        }
+        Ok(true)
+    }
+}
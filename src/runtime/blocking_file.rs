@@ -0,0 +1,68 @@
+//! File access for runtimes that don't have async files of their own, meant to be run on their
+//! blocking thread pools
+
+use std::{
+    fs::File,
+    io::{self, Read as _, Seek as _, Write as _},
+    sync::{Arc, Mutex},
+};
+
+use super::file_lock::FileLock;
+
+pub type SharedFile = Arc<Mutex<File>>;
+
+pub(super) fn open(path: &str) -> io::Result<SharedFile> {
+    let file = File::options()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)?;
+
+    Ok(Arc::new(Mutex::new(file)))
+}
+
+pub(super) fn read_string(file: &SharedFile) -> io::Result<String> {
+    let mut file = file.lock().unwrap();
+
+    // so it doesn't read in the middle of another process' write
+    let _lock = FileLock::shared(file.try_clone()?)?;
+    let mut output = String::new();
+    file.rewind()?;
+    file.read_to_string(&mut output)?;
+
+    Ok(output)
+}
+
+pub(super) fn write_str(file: &SharedFile, text: &str) -> io::Result<()> {
+    let mut file = file.lock().unwrap();
+
+    // so it doesn't write in the middle of another process' compare_and_write
+    let _lock = FileLock::exclusive(file.try_clone()?)?;
+    overwrite(&mut file, text)
+}
+
+pub(super) fn compare_and_write(file: &SharedFile, expected: &str, text: &str) -> io::Result<bool> {
+    let mut file = file.lock().unwrap();
+
+    // keeps other processes out until it's dropped
+    let _lock = FileLock::exclusive(file.try_clone()?)?;
+
+    let mut current = String::new();
+    file.rewind()?;
+    file.read_to_string(&mut current)?;
+
+    if current != expected {
+        return Ok(false);
+    }
+
+    overwrite(&mut file, text)?;
+    Ok(true)
+}
+
+fn overwrite(file: &mut File, text: &str) -> io::Result<()> {
+    file.rewind()?;
+    file.set_len(0)?;
+    file.write_all(text.as_bytes())?;
+    file.flush()
+}
@@ -0,0 +1,30 @@
+//! Advisory locks, so other processes don't see a file halfway through a write
+
+use std::{fs::File, io};
+
+/// Holds a lock on a file until it's dropped. It's taken through its own handle, which shares the
+/// open file description with the original one, so it covers both. Closing the handle alone
+/// wouldn't release it while the original is still open, so this unlocks explicitly.
+pub(super) struct FileLock(File);
+
+impl FileLock {
+    /// Blocks until nobody else holds any lock on the file
+    pub(super) fn exclusive(handle: File) -> io::Result<Self> {
+        handle.lock()?;
+        Ok(Self(handle))
+    }
+
+    /// Blocks until nobody else holds an exclusive lock on the file
+    pub(super) fn shared(handle: File) -> io::Result<Self> {
+        handle.lock_shared()?;
+        Ok(Self(handle))
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        if let Err(err) = self.0.unlock() {
+            tracing::error!(event = "file-unlock", err = ?err, "couldn't unlock file");
+        }
+    }
+}
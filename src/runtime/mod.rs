@@ -0,0 +1,67 @@
+#[cfg(feature = "runtime-async-std")]
+pub mod async_std;
+#[cfg(any(feature = "runtime-async-std", feature = "runtime-smol"))]
+mod blocking_file;
+mod file_lock;
+#[cfg(feature = "runtime-smol")]
+pub mod smol;
+#[cfg(feature = "runtime-tokio")]
+pub mod tokio;
+
+use std::{fmt::Debug, future::Future, time::Duration};
+
+/// An abstraction over runtimes, so they can be swappable.
+pub trait Runtime {
+    type File;
+    type Err;
+    /// Resolves to what the spawned future returned, or to an error if it panicked
+    type JoinHandle<Out>: Future<Output = Result<Out, Self::JoinError>> + Unpin;
+    type JoinError: Debug;
+
+    fn open_file(path: &str) -> impl Future<Output = Result<Self::File, Self::Err>>;
+
+    fn read_string(file: &Self::File) -> impl Future<Output = Result<String, Self::Err>>;
+
+    /// Takes the same lock as [`Runtime::compare_and_write`], so it can't write in the middle of
+    /// one in another process
+    fn write_str(file: &Self::File, text: &str) -> impl Future<Output = Result<(), Self::Err>>;
+
+    /// Writes `text` only if the file contents are still `expected`, returning whether it wrote.
+    /// Other processes must not be able to write the file in between.
+    fn compare_and_write(
+        file: &Self::File,
+        expected: &str,
+        text: &str,
+    ) -> impl Future<Output = Result<bool, Self::Err>>;
+
+    fn sleep(duration: Duration) -> impl Future<Output = ()>;
+
+    /// Runs `future`, returning `None` if it doesn't finish within `duration`
+    fn timeout<F>(duration: Duration, future: F) -> impl Future<Output = Option<F::Output>>
+    where
+        F: Future;
+
+    fn spawn<F>(future: F) -> Self::JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static;
+
+    /// Lets the task behind `handle` run to completion even though `handle` is dropped
+    fn detach<Out>(handle: Self::JoinHandle<Out>);
+}
+
+// if several runtimes are enabled, it's the first of tokio, async-std and smol
+cfg_if::cfg_if! {
+    if #[cfg(feature = "runtime-tokio")] {
+        /// A runtime implementation that is selected depending on feature flags
+        pub type RuntimeImpl = self::tokio::TokioRuntime;
+    } else if #[cfg(feature = "runtime-async-std")] {
+        /// A runtime implementation that is selected depending on feature flags
+        pub type RuntimeImpl = self::async_std::AsyncStdRuntime;
+    } else if #[cfg(feature = "runtime-smol")] {
+        /// A runtime implementation that is selected depending on feature flags
+        pub type RuntimeImpl = self::smol::SmolRuntime;
+    } else {
+        compile_error!("you need to select a runtime");
+    }
+}
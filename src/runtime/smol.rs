@@ -0,0 +1,70 @@
+use std::{future::Future, io, panic::AssertUnwindSafe, time::Duration};
+
+use futures_lite::FutureExt as _;
+
+use super::{
+    blocking_file::{self, SharedFile},
+    Runtime,
+};
+
+pub struct SmolRuntime;
+
+impl Runtime for SmolRuntime {
+    type File = SharedFile;
+    type Err = io::Error;
+    // panics are caught so they're reported like with the other runtimes
+    type JoinHandle<Out> = smol::Task<std::thread::Result<Out>>;
+    type JoinError = Box<dyn std::any::Any + Send>;
+
+    async fn open_file(path: &str) -> Result<Self::File, Self::Err> {
+        let path = path.to_string();
+        smol::unblock(move || blocking_file::open(&path)).await
+    }
+
+    async fn read_string(file: &Self::File) -> Result<String, Self::Err> {
+        let file = file.clone();
+        smol::unblock(move || blocking_file::read_string(&file)).await
+    }
+
+    async fn write_str(file: &Self::File, text: &str) -> Result<(), Self::Err> {
+        let (file, text) = (file.clone(), text.to_string());
+        smol::unblock(move || blocking_file::write_str(&file, &text)).await
+    }
+
+    async fn compare_and_write(
+        file: &Self::File,
+        expected: &str,
+        text: &str,
+    ) -> Result<bool, Self::Err> {
+        let (file, expected, text) = (file.clone(), expected.to_string(), text.to_string());
+        smol::unblock(move || blocking_file::compare_and_write(&file, &expected, &text)).await
+    }
+
+    async fn sleep(duration: Duration) {
+        smol::Timer::after(duration).await;
+    }
+
+    async fn timeout<F>(duration: Duration, future: F) -> Option<F::Output>
+    where
+        F: Future,
+    {
+        futures_lite::future::or(async { Some(future.await) }, async {
+            smol::Timer::after(duration).await;
+            None
+        })
+        .await
+    }
+
+    fn spawn<F>(future: F) -> Self::JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        smol::spawn(AssertUnwindSafe(future).catch_unwind())
+    }
+
+    // smol cancels tasks when their handle is dropped
+    fn detach<Out>(handle: Self::JoinHandle<Out>) {
+        handle.detach();
+    }
+}
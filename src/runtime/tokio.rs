@@ -0,0 +1,114 @@
+use std::{future::Future, time::Duration};
+
+use super::{file_lock::FileLock, Runtime};
+
+pub struct TokioRuntime;
+
+impl Runtime for TokioRuntime {
+    type File = tokio::sync::Mutex<tokio::fs::File>;
+    type Err = tokio::io::Error;
+    type JoinHandle<Out> = tokio::task::JoinHandle<Out>;
+    type JoinError = tokio::task::JoinError;
+
+    async fn open_file(path: &str) -> Result<Self::File, Self::Err> {
+        Ok(tokio::sync::Mutex::new(
+            tokio::fs::File::options()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(path)
+                .await?,
+        ))
+    }
+
+    async fn read_string(file: &Self::File) -> Result<String, Self::Err> {
+        use tokio::io::AsyncReadExt as _;
+        use tokio::io::AsyncSeekExt as _;
+
+        let mut output = String::new();
+        let mut file = file.lock().await;
+        // so it doesn't read in the middle of another process' write
+        let _lock = lock(&file, FileLock::shared).await?;
+        file.rewind().await?;
+        file.read_to_string(&mut output).await?;
+
+        Ok(output)
+    }
+
+    async fn write_str(file: &Self::File, text: &str) -> Result<(), Self::Err> {
+        let mut file = file.lock().await;
+
+        // so it doesn't write in the middle of another process' compare_and_write
+        let _lock = lock(&file, FileLock::exclusive).await?;
+        overwrite(&mut file, text).await
+    }
+
+    async fn compare_and_write(
+        file: &Self::File,
+        expected: &str,
+        text: &str,
+    ) -> Result<bool, Self::Err> {
+        use tokio::io::AsyncReadExt as _;
+        use tokio::io::AsyncSeekExt as _;
+
+        let mut file = file.lock().await;
+
+        // keeps other processes out until it's dropped
+        let _lock = lock(&file, FileLock::exclusive).await?;
+
+        let mut current = String::new();
+        file.rewind().await?;
+        file.read_to_string(&mut current).await?;
+
+        if current != expected {
+            return Ok(false);
+        }
+
+        overwrite(&mut file, text).await?;
+        Ok(true)
+    }
+
+    async fn sleep(duration: Duration) {
+        tokio::time::sleep(duration).await
+    }
+
+    async fn timeout<F>(duration: Duration, future: F) -> Option<F::Output>
+    where
+        F: Future,
+    {
+        tokio::time::timeout(duration, future).await.ok()
+    }
+
+    fn spawn<F>(future: F) -> Self::JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        tokio::spawn(future)
+    }
+
+    fn detach<Out>(handle: Self::JoinHandle<Out>) {
+        drop(handle);
+    }
+}
+
+/// Waits for the lock on a blocking thread. If the caller is cancelled in the meantime, the lock
+/// is still released once it's taken.
+async fn lock(
+    file: &tokio::fs::File,
+    lock: fn(std::fs::File) -> std::io::Result<FileLock>,
+) -> Result<FileLock, tokio::io::Error> {
+    let handle = file.try_clone().await?.into_std().await;
+    tokio::task::spawn_blocking(move || lock(handle)).await?
+}
+
+async fn overwrite(file: &mut tokio::fs::File, text: &str) -> Result<(), tokio::io::Error> {
+    use tokio::io::AsyncSeekExt as _;
+    use tokio::io::AsyncWriteExt as _;
+
+    file.rewind().await?;
+    file.set_len(0).await?;
+    file.write_all(text.as_bytes()).await?;
+    file.flush().await
+}
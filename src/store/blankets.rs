@@ -4,11 +4,10 @@ use std::{future::Future, pin::Pin};
 
 use crate::alias::DynErr;
 
-use super::SentinelStore;
+use super::{CommitOutcome, SentinelStore};
 
 // TODO can't I just async_trait::async_trait?
 impl<T> SentinelStore<T> for Box<dyn SentinelStore<T> + Send + Sync + 'static> {
-    #[must_use]
     #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
     fn current<'life0, 'async_trait>(
         &'life0 self,
@@ -20,7 +19,6 @@ impl<T> SentinelStore<T> for Box<dyn SentinelStore<T> + Send + Sync + 'static> {
         (**self).current()
     }
 
-    #[must_use]
     #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
     fn commit<'life0, 'async_trait>(
         &'life0 mut self,
@@ -32,6 +30,20 @@ impl<T> SentinelStore<T> for Box<dyn SentinelStore<T> + Send + Sync + 'static> {
     {
         (**self).commit(sentinel)
     }
+
+    #[allow(clippy::type_complexity, clippy::type_repetition_in_bounds)]
+    fn commit_if<'life0, 'async_trait>(
+        &'life0 mut self,
+        expected: Option<T>,
+        sentinel: T,
+    ) -> Pin<Box<dyn Future<Output = Result<CommitOutcome, DynErr>> + Send + 'async_trait>>
+    where
+        T: Send + 'async_trait,
+        'life0: 'async_trait,
+        Self: 'async_trait,
+    {
+        (**self).commit_if(expected, sentinel)
+    }
 }
 
 #[cfg(test)]
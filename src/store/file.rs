@@ -2,6 +2,7 @@ use crate::DynErr;
 use std::str::FromStr;
 
 use crate::runtime::{Runtime, RuntimeImpl};
+use crate::store::CommitOutcome;
 use crate::SentinelStore;
 
 pub struct FileSentinelStore {
@@ -21,6 +22,28 @@ impl<Sentinel: FileStorableSentinel> SentinelStore<Sentinel> for FileSentinelSto
             .then(|| Sentinel::from_str(&current_sentinel_string))
             .transpose()?)
     }
+
+    async fn commit_if(
+        &mut self,
+        expected: Option<Sentinel>,
+        sentinel: Sentinel,
+    ) -> Result<CommitOutcome, DynErr> {
+        let expected = expected.map(|it| it.to_string());
+        loop {
+            let contents = RuntimeImpl::read_string(&self.file).await?;
+            let stored = (!contents.is_empty())
+                .then(|| canonical::<Sentinel>(&contents))
+                .transpose()?;
+            if stored != expected {
+                return Ok(CommitOutcome::Conflict);
+            }
+
+            // only fails if it was written in between, so it's checked again
+            if RuntimeImpl::compare_and_write(&self.file, &contents, &sentinel.to_string()).await? {
+                return Ok(CommitOutcome::Committed);
+            }
+        }
+    }
 }
 
 impl FileSentinelStore {
@@ -31,6 +54,12 @@ impl FileSentinelStore {
     }
 }
 
+/// How `stored` would be written by this crate, so sentinels are compared by value instead of by
+/// how they're written, e.g. `007` and `7`
+pub(crate) fn canonical<Sentinel: FileStorableSentinel>(stored: &str) -> Result<String, DynErr> {
+    Ok(Sentinel::from_str(stored)?.to_string())
+}
+
 /// A sentinel that can be stored in a file.
 ///
 /// This storage uses [`ToString`] to save and [`FromStr`] to retrieve, so a sentinel has to
@@ -50,3 +79,46 @@ where
 {
     type ParseErr = <Self as FromStr>::Err;
 }
+
+#[cfg(test)]
+mod tests {
+    use rand::distributions::DistString as _;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn commit_if_conflicts_with_another_store_on_the_same_file() {
+        let test_id = rand::distributions::Alphanumeric.sample_string(&mut rand::thread_rng(), 10);
+        let file_path = format!("/tmp/mrprober-test-{test_id}");
+        let mut first = FileSentinelStore::open(&file_path).await.unwrap();
+        let mut second = FileSentinelStore::open(&file_path).await.unwrap();
+
+        first.commit(1).await.unwrap();
+
+        assert_eq!(
+            second.commit_if(None, 2).await.unwrap(),
+            CommitOutcome::Conflict
+        );
+        assert_eq!(
+            second.commit_if(Some(1), 2).await.unwrap(),
+            CommitOutcome::Committed
+        );
+        assert_eq!(
+            SentinelStore::<u32>::current(&first).await.unwrap(),
+            Some(2)
+        );
+    }
+
+    #[tokio::test]
+    async fn commit_if_compares_sentinels_by_value() {
+        let test_id = rand::distributions::Alphanumeric.sample_string(&mut rand::thread_rng(), 10);
+        let file_path = format!("/tmp/mrprober-test-{test_id}");
+        let mut store = FileSentinelStore::open(&file_path).await.unwrap();
+        RuntimeImpl::write_str(&store.file, "007").await.unwrap();
+
+        assert_eq!(
+            store.commit_if(Some(7_u64), 8).await.unwrap(),
+            CommitOutcome::Committed
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    alias::DynErr,
+    runtime::{Runtime, RuntimeImpl},
+    store::{
+        file::{canonical, FileStorableSentinel},
+        CommitOutcome,
+    },
+};
+
+use super::KeyedSentinelStore;
+
+/// Holds many sentinels in a single JSON file, as an object from keys to sentinels.
+///
+/// Sentinels are saved with [`ToString`] and retrieved with [`FromStr`](std::str::FromStr), just
+/// like in a [`FileSentinelStore`](crate::store::file::FileSentinelStore).
+pub struct JsonFileKeyedSentinelStore {
+    file: <RuntimeImpl as Runtime>::File,
+}
+
+impl JsonFileKeyedSentinelStore {
+    pub async fn open(file_path: &str) -> Result<Self, <RuntimeImpl as Runtime>::Err> {
+        Ok(Self {
+            file: RuntimeImpl::open_file(file_path).await?,
+        })
+    }
+
+    async fn read(&self) -> Result<(String, BTreeMap<String, String>), DynErr> {
+        let contents = RuntimeImpl::read_string(&self.file).await?;
+        let sentinels = if contents.trim().is_empty() {
+            BTreeMap::new()
+        } else {
+            serde_json::from_str(&contents)?
+        };
+
+        Ok((contents, sentinels))
+    }
+
+    /// Applies `update` to the stored sentinels, starting over if the file was changed by someone
+    /// else in the meantime. Nothing is written if `update` returns `false`.
+    async fn update(
+        &self,
+        mut update: impl FnMut(&mut BTreeMap<String, String>) -> bool + Send,
+    ) -> Result<bool, DynErr> {
+        loop {
+            let (contents, mut sentinels) = self.read().await?;
+            if !update(&mut sentinels) {
+                return Ok(false);
+            }
+
+            let new_contents = serde_json::to_string(&sentinels)?;
+            if RuntimeImpl::compare_and_write(&self.file, &contents, &new_contents).await? {
+                return Ok(true);
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<Sentinel: FileStorableSentinel> KeyedSentinelStore<Sentinel> for JsonFileKeyedSentinelStore {
+    async fn current(&self, key: &str) -> Result<Option<Sentinel>, DynErr> {
+        let (_, sentinels) = self.read().await?;
+
+        Ok(sentinels
+            .get(key)
+            .map(|it| Sentinel::from_str(it))
+            .transpose()?)
+    }
+
+    async fn commit(&self, key: &str, sentinel: Sentinel) -> Result<(), DynErr> {
+        let sentinel = sentinel.to_string();
+        self.update(|sentinels| {
+            sentinels.insert(key.to_string(), sentinel.clone());
+            true
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    async fn commit_if(
+        &self,
+        key: &str,
+        expected: Option<Sentinel>,
+        sentinel: Sentinel,
+    ) -> Result<CommitOutcome, DynErr> {
+        let expected = expected.map(|it| it.to_string());
+        let sentinel = sentinel.to_string();
+        let mut parse_err = None;
+        let committed = self
+            .update(|sentinels| {
+                let stored = sentinels.get(key).map(|it| canonical::<Sentinel>(it));
+                match stored.transpose() {
+                    Ok(stored) if stored == expected => {}
+                    Ok(_) => return false,
+                    Err(err) => {
+                        parse_err = Some(err);
+                        return false;
+                    }
+                }
+
+                sentinels.insert(key.to_string(), sentinel.clone());
+                true
+            })
+            .await?;
+        if let Some(err) = parse_err {
+            return Err(err);
+        }
+
+        Ok(if committed {
+            CommitOutcome::Committed
+        } else {
+            CommitOutcome::Conflict
+        })
+    }
+}
@@ -0,0 +1,99 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::{
+    alias::DynErr,
+    store::{mem::MemoryStorableSentinel, CommitOutcome, ConditionalCommitUnsupported},
+};
+
+use super::KeyedSentinelStore;
+
+/// Holds many sentinels in memory
+pub struct MemoryKeyedSentinelStore<Sentinel> {
+    sentinels: Mutex<HashMap<String, Sentinel>>,
+    /// Only there for sentinels that can be compared, which conditional commits need
+    same: Option<fn(&Sentinel, &Sentinel) -> bool>,
+}
+
+impl<Sentinel> Default for MemoryKeyedSentinelStore<Sentinel> {
+    fn default() -> Self {
+        Self {
+            sentinels: Mutex::new(HashMap::new()),
+            same: None,
+        }
+    }
+}
+
+impl<Sentinel: PartialEq> MemoryKeyedSentinelStore<Sentinel> {
+    /// A store that also supports [`KeyedSentinelStore::commit_if`], which the default one
+    /// doesn't
+    pub fn comparable() -> Self {
+        Self {
+            sentinels: Mutex::new(HashMap::new()),
+            same: Some(Sentinel::eq),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<Sentinel: MemoryStorableSentinel> KeyedSentinelStore<Sentinel>
+    for MemoryKeyedSentinelStore<Sentinel>
+{
+    async fn current(&self, key: &str) -> Result<Option<Sentinel>, DynErr> {
+        Ok(self.sentinels.lock().unwrap().get(key).cloned())
+    }
+
+    async fn commit(&self, key: &str, sentinel: Sentinel) -> Result<(), DynErr> {
+        self.sentinels
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), sentinel);
+        Ok(())
+    }
+
+    async fn commit_if(
+        &self,
+        key: &str,
+        expected: Option<Sentinel>,
+        sentinel: Sentinel,
+    ) -> Result<CommitOutcome, DynErr> {
+        let Some(same) = self.same else {
+            return Err(ConditionalCommitUnsupported.into());
+        };
+        let mut sentinels = self.sentinels.lock().unwrap();
+        let unchanged = match (sentinels.get(key), &expected) {
+            (Some(stored), Some(expected)) => same(stored, expected),
+            (stored, expected) => stored.is_none() && expected.is_none(),
+        };
+        if !unchanged {
+            return Ok(CommitOutcome::Conflict);
+        }
+
+        sentinels.insert(key.to_string(), sentinel);
+        Ok(CommitOutcome::Committed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::store::SentinelStore;
+
+    #[tokio::test]
+    async fn namespaces_are_independent() {
+        let store = Arc::new(MemoryKeyedSentinelStore::comparable());
+        let mut first = store.namespace("first");
+        let mut second = store.namespace("second");
+
+        first.commit(1).await.unwrap();
+        second.commit(2).await.unwrap();
+
+        assert_eq!(first.current().await.unwrap(), Some(1));
+        assert_eq!(second.current().await.unwrap(), Some(2));
+        assert_eq!(
+            second.commit_if(Some(1), 3).await.unwrap(),
+            CommitOutcome::Conflict
+        );
+    }
+}
@@ -0,0 +1,73 @@
+//! Stores that hold many sentinels, each under its own key
+
+#[cfg(feature = "file")]
+pub mod file;
+pub mod mem;
+
+use std::sync::Arc;
+
+use crate::alias::DynErr;
+
+use super::{CommitOutcome, SentinelStore};
+
+/// A store that holds many named sentinels.
+///
+/// It's meant to be shared by several probers, each of them using its own
+/// [`NamespacedStore`] view of it.
+#[async_trait::async_trait]
+pub trait KeyedSentinelStore<Sentinel>: Send + Sync {
+    async fn current(&self, key: &str) -> Result<Option<Sentinel>, DynErr>;
+    async fn commit(&self, key: &str, sentinel: Sentinel) -> Result<(), DynErr>;
+    async fn commit_if(
+        &self,
+        key: &str,
+        expected: Option<Sentinel>,
+        sentinel: Sentinel,
+    ) -> Result<CommitOutcome, DynErr>;
+
+    /// A view of the sentinel under `key` that can be used as a regular [`SentinelStore`]
+    fn namespace(self: &Arc<Self>, key: impl Into<String>) -> NamespacedStore<Self>
+    where
+        Self: Sized,
+    {
+        NamespacedStore {
+            store: Arc::clone(self),
+            key: key.into(),
+        }
+    }
+}
+
+/// The sentinel under a single key of a [`KeyedSentinelStore`]
+pub struct NamespacedStore<Store> {
+    store: Arc<Store>,
+    key: String,
+}
+
+impl<Store> NamespacedStore<Store> {
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+#[async_trait::async_trait]
+impl<Sentinel, Store> SentinelStore<Sentinel> for NamespacedStore<Store>
+where
+    Sentinel: Send + 'static,
+    Store: KeyedSentinelStore<Sentinel>,
+{
+    async fn current(&self) -> Result<Option<Sentinel>, DynErr> {
+        self.store.current(&self.key).await
+    }
+
+    async fn commit(&mut self, sentinel: Sentinel) -> Result<(), DynErr> {
+        self.store.commit(&self.key, sentinel).await
+    }
+
+    async fn commit_if(
+        &mut self,
+        expected: Option<Sentinel>,
+        sentinel: Sentinel,
+    ) -> Result<CommitOutcome, DynErr> {
+        self.store.commit_if(&self.key, expected, sentinel).await
+    }
+}
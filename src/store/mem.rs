@@ -1,4 +1,8 @@
-use crate::{alias::DynErr, SentinelStore};
+use crate::{
+    alias::DynErr,
+    store::{CommitOutcome, ConditionalCommitUnsupported},
+    SentinelStore,
+};
 
 #[async_trait::async_trait]
 impl<Sentinel: MemoryStorableSentinel> SentinelStore<Sentinel> for MemorySentinelStore<Sentinel> {
@@ -10,6 +14,26 @@ impl<Sentinel: MemoryStorableSentinel> SentinelStore<Sentinel> for MemorySentine
         self.sentinel.replace(sentinel);
         Ok(())
     }
+
+    async fn commit_if(
+        &mut self,
+        expected: Option<Sentinel>,
+        sentinel: Sentinel,
+    ) -> Result<CommitOutcome, DynErr> {
+        let Some(same) = self.same else {
+            return Err(ConditionalCommitUnsupported.into());
+        };
+        let unchanged = match (&self.sentinel, &expected) {
+            (Some(stored), Some(expected)) => same(stored, expected),
+            (stored, expected) => stored.is_none() && expected.is_none(),
+        };
+        if !unchanged {
+            return Ok(CommitOutcome::Conflict);
+        }
+
+        self.sentinel.replace(sentinel);
+        Ok(CommitOutcome::Committed)
+    }
 }
 
 /// A sentinel that can be stored in a memory.
@@ -19,10 +43,65 @@ impl<T> MemoryStorableSentinel for T where T: Clone + Send + Sync + 'static {}
 
 pub struct MemorySentinelStore<Sentinel> {
     sentinel: Option<Sentinel>,
+    /// Only there for sentinels that can be compared, which conditional commits need
+    same: Option<fn(&Sentinel, &Sentinel) -> bool>,
 }
 
 impl<T> Default for MemorySentinelStore<T> {
     fn default() -> Self {
-        Self { sentinel: None }
+        Self {
+            sentinel: None,
+            same: None,
+        }
+    }
+}
+
+impl<T: PartialEq> MemorySentinelStore<T> {
+    /// A store that also supports [`SentinelStore::commit_if`], which the default one doesn't
+    pub fn comparable() -> Self {
+        Self {
+            sentinel: None,
+            same: Some(T::eq),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn commit_if_conflicts_on_stale_expectation() {
+        let mut store = MemorySentinelStore::comparable();
+        store.commit(1).await.unwrap();
+
+        assert_eq!(
+            store.commit_if(None, 2).await.unwrap(),
+            CommitOutcome::Conflict
+        );
+        assert_eq!(
+            store.commit_if(Some(1), 2).await.unwrap(),
+            CommitOutcome::Committed
+        );
+        assert_eq!(store.current().await.unwrap(), Some(2));
+    }
+
+    #[tokio::test]
+    async fn commit_if_needs_a_comparable_store() {
+        let mut store = MemorySentinelStore::default();
+
+        let err = store.commit_if(None, 1).await.unwrap_err();
+        assert!(err.is::<ConditionalCommitUnsupported>());
+    }
+
+    #[tokio::test]
+    async fn stores_sentinels_that_cannot_be_compared() {
+        #[derive(Clone)]
+        struct Opaque(u32);
+
+        let mut store = MemorySentinelStore::default();
+        store.commit(Opaque(1)).await.unwrap();
+
+        assert!(matches!(store.current().await.unwrap(), Some(Opaque(1))));
     }
 }
@@ -1,8 +1,11 @@
 #[cfg(feature = "file")]
 mod blankets;
 pub mod file;
+pub mod keyed;
 pub mod mem;
 
+use thiserror::Error;
+
 use crate::alias::DynErr;
 
 #[async_trait::async_trait]
@@ -10,4 +13,32 @@ use crate::alias::DynErr;
 pub trait SentinelStore<Sentinel> {
     async fn current(&self) -> Result<Option<Sentinel>, DynErr>;
     async fn commit(&mut self, sentinel: Sentinel) -> Result<(), DynErr>;
+
+    /// Commits `sentinel` only if the stored sentinel is still `expected`, which should be what
+    /// [`SentinelStore::current`] returned.
+    ///
+    /// Stores that can't do this return a [`ConditionalCommitUnsupported`] error.
+    async fn commit_if(
+        &mut self,
+        _expected: Option<Sentinel>,
+        _sentinel: Sentinel,
+    ) -> Result<CommitOutcome, DynErr>
+    where
+        Sentinel: Send + 'async_trait,
+    {
+        Err(ConditionalCommitUnsupported.into())
+    }
 }
+
+/// What comes out of a conditional commit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommitOutcome {
+    /// The sentinel was committed
+    Committed,
+    /// The stored sentinel was changed by someone else, so nothing was committed
+    Conflict,
+}
+
+#[derive(Error, Debug)]
+#[error("this store doesn't support conditional commits")]
+pub struct ConditionalCommitUnsupported;
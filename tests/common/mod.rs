@@ -0,0 +1,19 @@
+use std::future::Future;
+
+/// Runs `future` to completion on whichever runtime the crate is built with, the first of tokio,
+/// async-std and smol if there are several
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "runtime-tokio")] {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("should be able to build a runtime")
+                .block_on(future)
+        } else if #[cfg(feature = "runtime-async-std")] {
+            async_std::task::block_on(future)
+        } else if #[cfg(feature = "runtime-smol")] {
+            smol::block_on(future)
+        }
+    }
+}
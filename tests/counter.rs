@@ -1,65 +1,230 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
-use mr_prober::{proc::Processor, Prober};
+use mr_prober::{
+    auto::exit::ExitReason,
+    lease::{file::FileLease, Lease as _},
+    proc::{next::Next, Processor},
+    runtime::{Runtime as _, RuntimeImpl},
+    store::keyed::file::JsonFileKeyedSentinelStore,
+    Prober,
+};
 use rand::distributions::DistString;
 
-#[tokio::test]
-async fn in_memory() {
-    // ARRANGE
-    let counter = Arc::new(Mutex::new(Counter::default()));
+mod common;
 
-    let mut prober = Prober::in_memory(CounterProcessor::new(Arc::clone(&counter)));
+// these run on whichever runtime the crate is built with, so CI runs them with each of the
+// `runtime-*` features on its own
 
-    // ACT
-    for _ in 0..10 {
-        prober.probe().await.expect_ok();
-    }
+#[test]
+fn in_memory() {
+    common::block_on(async {
+        // ARRANGE
+        let counter = Arc::new(Mutex::new(Counter::default()));
+
+        let mut prober = Prober::in_memory(CounterProcessor::new(Arc::clone(&counter)));
+
+        // ACT
+        for _ in 0..10 {
+            prober.probe().await.expect_ok();
+        }
 
-    // ASSERT
-    assert_eq!(
-        counter.lock().unwrap().interactions,
-        vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
-    );
+        // ASSERT
+        assert_eq!(
+            counter.lock().unwrap().interactions,
+            vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+        );
+    })
 }
 
-#[tokio::test]
-async fn in_file() {
-    // ARRANGE
-    let counter = Arc::new(Mutex::new(Counter::default()));
+#[test]
+fn in_file() {
+    common::block_on(async {
+        // ARRANGE
+        let counter = Arc::new(Mutex::new(Counter::default()));
 
-    let test_id = rand::distributions::Alphanumeric.sample_string(&mut rand::thread_rng(), 10);
-    let file_path = format!("/tmp/mrprober-test-{test_id}");
-    let mut prober = Prober::from_file(&file_path, CounterProcessor::new(Arc::clone(&counter)))
-        .await
-        .unwrap();
+        let test_id = rand::distributions::Alphanumeric.sample_string(&mut rand::thread_rng(), 10);
+        let file_path = format!("/tmp/mrprober-test-{test_id}");
+        let mut prober = Prober::from_file(&file_path, CounterProcessor::new(Arc::clone(&counter)))
+            .await
+            .unwrap();
 
-    // ACT
-    for _ in 0..10 {
-        prober.probe().await.expect_ok();
-    }
+        // ACT
+        for _ in 0..10 {
+            prober.probe().await.expect_ok();
+        }
+
+        // ASSERT
+        assert_eq!(
+            counter.lock().unwrap().interactions,
+            vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+        );
+    })
+}
 
-    // ASSERT
-    assert_eq!(
-        counter.lock().unwrap().interactions,
-        vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
-    );
+#[test]
+fn in_keyed_file() {
+    common::block_on(async {
+        // ARRANGE
+        let first_counter = Arc::new(Mutex::new(Counter::default()));
+        let second_counter = Arc::new(Mutex::new(Counter::default()));
+
+        let test_id = rand::distributions::Alphanumeric.sample_string(&mut rand::thread_rng(), 10);
+        let file_path = format!("/tmp/mrprober-test-{test_id}.json");
+        let store = Arc::new(JsonFileKeyedSentinelStore::open(&file_path).await.unwrap());
+        let mut first_prober = Prober::namespaced(
+            &store,
+            "first",
+            CounterProcessor::new(Arc::clone(&first_counter)),
+        );
+        let mut second_prober = Prober::namespaced(
+            &store,
+            "second",
+            CounterProcessor::new(Arc::clone(&second_counter)),
+        );
+
+        // ACT
+        for _ in 0..10 {
+            first_prober.probe().await.expect_ok();
+        }
+        for _ in 0..5 {
+            second_prober.probe().await.expect_ok();
+        }
+
+        // ASSERT
+        assert_eq!(
+            first_counter.lock().unwrap().interactions,
+            vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+        );
+        assert_eq!(
+            second_counter.lock().unwrap().interactions,
+            vec![0, 1, 2, 3, 4]
+        );
+        assert_eq!(
+            std::fs::read_to_string(&file_path).unwrap(),
+            r#"{"first":"10","second":"5"}"#
+        );
+    })
 }
 
-#[tokio::test]
-async fn auto_prober() {
-    // ARRANGE
-    let counter = Arc::new(Mutex::new(Counter::default()));
+#[test]
+fn auto_prober() {
+    common::block_on(async {
+        // ARRANGE
+        let counter = Arc::new(Mutex::new(Counter::default()));
+
+        let prober = Prober::in_memory(CounterProcessor::new(Arc::clone(&counter)));
 
-    let prober = Prober::in_memory(CounterProcessor::new(Arc::clone(&counter)));
+        // ACT
+        let exit = prober.into_auto(Default::default()).spawn().await.unwrap();
+
+        // ASSERT
+        assert!(matches!(exit.reason, ExitReason::AbortedOnEmpty));
+        assert_eq!(
+            counter.lock().unwrap().interactions,
+            vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+        );
+    })
+}
 
-    // ACT
-    prober.into_auto(Default::default()).spawn().await.unwrap();
+#[test]
+fn auto_prober_waits_for_lease() {
+    common::block_on(async {
+        // ARRANGE
+        let counter = Arc::new(Mutex::new(Counter::default()));
+
+        let test_id = rand::distributions::Alphanumeric.sample_string(&mut rand::thread_rng(), 10);
+        let lock_path = format!("/tmp/mrprober-test-{test_id}.lock");
+        let mut other_replica = FileLease::open(&lock_path, Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert!(other_replica.acquire().await.unwrap());
+
+        let prober = Prober::in_memory(CounterProcessor::new(Arc::clone(&counter)));
+        let lease = FileLease::open(&lock_path, Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        // ACT
+        let handle = prober
+            .into_auto(Default::default())
+            .with_lease(lease, Duration::from_secs(1))
+            .spawn();
+        RuntimeImpl::sleep(Duration::from_millis(1500)).await;
+        assert!(counter.lock().unwrap().interactions.is_empty());
+
+        other_replica.release().await.unwrap();
+        handle.await.unwrap();
+
+        // ASSERT
+        assert_eq!(
+            counter.lock().unwrap().interactions,
+            vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
+        );
+    })
+}
+
+#[test]
+fn auto_prober_probes_as_soon_as_it_gets_the_lease() {
+    common::block_on(async {
+        // ARRANGE
+        let counter = Arc::new(Mutex::new(Counter::default()));
+
+        let test_id = rand::distributions::Alphanumeric.sample_string(&mut rand::thread_rng(), 10);
+        let lock_path = format!("/tmp/mrprober-test-{test_id}.lock");
+        let prober = Prober::in_memory(CounterProcessor::new(Arc::clone(&counter)));
+        let lease = FileLease::open(&lock_path, Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        // ACT
+        let started = Instant::now();
+        prober
+            .into_auto(Default::default())
+            .with_lease(lease, Duration::from_secs(10))
+            .spawn()
+            .await
+            .unwrap();
+
+        // ASSERT
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(counter.lock().unwrap().interactions.len(), 10);
+    })
+}
+
+#[test]
+fn auto_prober_releases_the_lease_when_it_stops() {
+    common::block_on(async {
+        // ARRANGE
+        let counter = Arc::new(Mutex::new(Counter::default()));
+
+        let test_id = rand::distributions::Alphanumeric.sample_string(&mut rand::thread_rng(), 10);
+        let lock_path = format!("/tmp/mrprober-test-{test_id}.lock");
+        let prober = Prober::in_memory(CounterProcessor::new(Arc::clone(&counter)));
+        let lease = FileLease::open(&lock_path, Duration::from_secs(60))
+            .await
+            .unwrap();
+        let mut other_replica = FileLease::open(&lock_path, Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        // ACT
+        prober
+            .into_auto(Default::default())
+            .with_lease(lease, Duration::from_secs(10))
+            .spawn()
+            .await
+            .unwrap();
+        let started = Instant::now();
+        while !other_replica.acquire().await.unwrap() {
+            RuntimeImpl::sleep(Duration::from_millis(10)).await;
+        }
 
-    // ASSERT
-    assert_eq!(
-        counter.lock().unwrap().interactions,
-        vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]
-    );
+        // ASSERT
+        assert!(started.elapsed() < Duration::from_secs(5));
+    })
 }
 
 #[derive(Default)]
@@ -92,13 +257,13 @@ impl Processor for CounterProcessor {
     async fn next(
         &self,
         current: Option<u64>,
-    ) -> Result<Option<u64>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    ) -> Result<Next<u64>, Box<dyn std::error::Error + Send + Sync + 'static>> {
         if current.is_some_and(|it| it >= 10) {
-            return Ok(None);
+            return Ok(Next::empty());
         }
 
         let next = self.counter.lock().unwrap().interact(current.unwrap_or(0));
 
-        Ok(Some(next))
+        Ok(Next::advance(next))
     }
 }
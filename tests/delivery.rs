@@ -0,0 +1,300 @@
+//! Simulates a crash at each step of a probe by failing it, dropping the prober, and building a
+//! new one over the same store
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+use mr_prober::{
+    commit::CommitRetryPolicy,
+    delivery::DeliveryMode,
+    proc::{next::Next, Processor},
+    store::SentinelStore,
+    ProbeResult, Prober,
+};
+
+mod common;
+
+#[test]
+fn at_least_once_crash_before_execute() {
+    common::block_on(async {
+        // ARRANGE
+        let store = SharedStore::default();
+        let ledger = Ledger::default();
+        ledger.fail_execute.store(true, Ordering::SeqCst);
+
+        // ACT
+        let mut prober = build_prober(&store, &ledger, DeliveryMode::AtLeastOnce);
+        assert!(matches!(prober.probe().await, ProbeResult::Error(_)));
+        drop(prober);
+
+        ledger.fail_execute.store(false, Ordering::SeqCst);
+        let mut prober = build_prober(&store, &ledger, DeliveryMode::AtLeastOnce);
+        prober.probe().await.expect_ok();
+
+        // ASSERT
+        assert_eq!(*ledger.executed.lock().unwrap(), vec![1]);
+        assert_eq!(*ledger.acknowledged.lock().unwrap(), vec![1]);
+        assert_eq!(*store.sentinel.lock().unwrap(), Some(1));
+    })
+}
+
+#[test]
+fn at_least_once_crash_before_commit() {
+    common::block_on(async {
+        // ARRANGE
+        let store = SharedStore::default();
+        let ledger = Ledger::default();
+        store.fail_commit.store(true, Ordering::SeqCst);
+
+        // ACT
+        let mut prober = build_prober(&store, &ledger, DeliveryMode::AtLeastOnce);
+        assert!(matches!(
+            prober.probe().await,
+            ProbeResult::PendingCommit(_)
+        ));
+        drop(prober);
+
+        store.fail_commit.store(false, Ordering::SeqCst);
+        let mut prober = build_prober(&store, &ledger, DeliveryMode::AtLeastOnce);
+        prober.probe().await.expect_ok();
+
+        // ASSERT
+        assert_eq!(*ledger.executed.lock().unwrap(), vec![1, 1]);
+        assert_eq!(*ledger.acknowledged.lock().unwrap(), vec![1]);
+        assert_eq!(*store.sentinel.lock().unwrap(), Some(1));
+    })
+}
+
+#[test]
+fn at_least_once_crash_before_acknowledge() {
+    common::block_on(async {
+        // ARRANGE
+        let store = SharedStore::default();
+        let ledger = Ledger::default();
+        ledger.fail_acknowledge.store(true, Ordering::SeqCst);
+
+        // ACT
+        let mut prober = build_prober(&store, &ledger, DeliveryMode::AtLeastOnce);
+        assert!(matches!(prober.probe().await, ProbeResult::Error(_)));
+        drop(prober);
+
+        ledger.fail_acknowledge.store(false, Ordering::SeqCst);
+        let mut prober = build_prober(&store, &ledger, DeliveryMode::AtLeastOnce);
+        prober.probe().await.expect_ok();
+
+        // ASSERT
+        // it was committed already, so it's not executed again
+        assert_eq!(*ledger.executed.lock().unwrap(), vec![1, 2]);
+        assert_eq!(*ledger.acknowledged.lock().unwrap(), vec![2]);
+        assert_eq!(*store.sentinel.lock().unwrap(), Some(2));
+    })
+}
+
+#[test]
+fn at_least_once_acknowledge_fails() {
+    common::block_on(async {
+        // ARRANGE
+        let store = SharedStore::default();
+        let ledger = Ledger::default();
+        ledger.fail_acknowledge.store(true, Ordering::SeqCst);
+        let mut prober = build_prober(&store, &ledger, DeliveryMode::AtLeastOnce);
+
+        // ACT
+        let report = prober.probe_report().await;
+
+        // ASSERT
+        assert!(matches!(report.result, ProbeResult::Error(_)));
+        assert_eq!(report.committed, Some(1));
+        assert_eq!(*ledger.executed.lock().unwrap(), vec![1]);
+        assert!(ledger.acknowledged.lock().unwrap().is_empty());
+        assert_eq!(*store.sentinel.lock().unwrap(), Some(1));
+    })
+}
+
+#[test]
+fn at_most_once_crash_before_commit() {
+    common::block_on(async {
+        // ARRANGE
+        let store = SharedStore::default();
+        let ledger = Ledger::default();
+        store.fail_commit.store(true, Ordering::SeqCst);
+
+        // ACT
+        let mut prober = build_prober(&store, &ledger, DeliveryMode::AtMostOnce);
+        assert!(matches!(prober.probe().await, ProbeResult::Error(_)));
+        assert!(ledger.executed.lock().unwrap().is_empty());
+        drop(prober);
+
+        store.fail_commit.store(false, Ordering::SeqCst);
+        let mut prober = build_prober(&store, &ledger, DeliveryMode::AtMostOnce);
+        prober.probe().await.expect_ok();
+
+        // ASSERT
+        assert_eq!(*ledger.executed.lock().unwrap(), vec![1]);
+        assert_eq!(*ledger.acknowledged.lock().unwrap(), vec![1]);
+        assert_eq!(*store.sentinel.lock().unwrap(), Some(1));
+    })
+}
+
+#[test]
+fn at_most_once_crash_before_execute() {
+    common::block_on(async {
+        // ARRANGE
+        let store = SharedStore::default();
+        let ledger = Ledger::default();
+        ledger.fail_execute.store(true, Ordering::SeqCst);
+
+        // ACT
+        let mut prober = build_prober(&store, &ledger, DeliveryMode::AtMostOnce);
+        assert!(matches!(prober.probe().await, ProbeResult::Error(_)));
+        drop(prober);
+
+        ledger.fail_execute.store(false, Ordering::SeqCst);
+        let mut prober = build_prober(&store, &ledger, DeliveryMode::AtMostOnce);
+        prober.probe().await.expect_ok();
+
+        // ASSERT
+        assert_eq!(*ledger.executed.lock().unwrap(), vec![2]);
+        assert_eq!(*ledger.acknowledged.lock().unwrap(), vec![2]);
+        assert_eq!(*store.sentinel.lock().unwrap(), Some(2));
+    })
+}
+
+#[test]
+fn at_most_once_crash_before_acknowledge() {
+    common::block_on(async {
+        // ARRANGE
+        let store = SharedStore::default();
+        let ledger = Ledger::default();
+        ledger.fail_acknowledge.store(true, Ordering::SeqCst);
+
+        // ACT
+        let mut prober = build_prober(&store, &ledger, DeliveryMode::AtMostOnce);
+        assert!(matches!(prober.probe().await, ProbeResult::Error(_)));
+        drop(prober);
+
+        ledger.fail_acknowledge.store(false, Ordering::SeqCst);
+        let mut prober = build_prober(&store, &ledger, DeliveryMode::AtMostOnce);
+        prober.probe().await.expect_ok();
+
+        // ASSERT
+        // it was committed already, so it's not executed again
+        assert_eq!(*ledger.executed.lock().unwrap(), vec![1, 2]);
+        assert_eq!(*ledger.acknowledged.lock().unwrap(), vec![2]);
+        assert_eq!(*store.sentinel.lock().unwrap(), Some(2));
+    })
+}
+
+#[test]
+fn at_most_once_acknowledge_fails() {
+    common::block_on(async {
+        // ARRANGE
+        let store = SharedStore::default();
+        let ledger = Ledger::default();
+        ledger.fail_acknowledge.store(true, Ordering::SeqCst);
+        let mut prober = build_prober(&store, &ledger, DeliveryMode::AtMostOnce);
+
+        // ACT
+        let report = prober.probe_report().await;
+
+        // ASSERT
+        assert!(matches!(report.result, ProbeResult::Error(_)));
+        assert_eq!(report.committed, Some(1));
+        assert_eq!(*ledger.executed.lock().unwrap(), vec![1]);
+        assert!(ledger.acknowledged.lock().unwrap().is_empty());
+        assert_eq!(*store.sentinel.lock().unwrap(), Some(1));
+    })
+}
+
+fn build_prober(
+    store: &SharedStore,
+    ledger: &Ledger,
+    delivery: DeliveryMode,
+) -> Prober<SharedStore, u64, LedgerProcessor> {
+    Prober::new(
+        store.clone(),
+        LedgerProcessor {
+            ledger: ledger.clone(),
+        },
+    )
+    .with_delivery(delivery)
+    .with_commit_retry(CommitRetryPolicy::none())
+}
+
+/// A store whose contents survive the prober being dropped
+#[derive(Clone, Default)]
+struct SharedStore {
+    sentinel: Arc<Mutex<Option<u64>>>,
+    fail_commit: Arc<AtomicBool>,
+}
+
+#[async_trait::async_trait]
+impl SentinelStore<u64> for SharedStore {
+    async fn current(&self) -> Result<Option<u64>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(*self.sentinel.lock().unwrap())
+    }
+
+    async fn commit(
+        &mut self,
+        sentinel: u64,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if self.fail_commit.load(Ordering::SeqCst) {
+            return Err("crashed while committing".into());
+        }
+
+        self.sentinel.lock().unwrap().replace(sentinel);
+        Ok(())
+    }
+}
+
+#[derive(Clone, Default)]
+struct Ledger {
+    executed: Arc<Mutex<Vec<u64>>>,
+    acknowledged: Arc<Mutex<Vec<u64>>>,
+    fail_execute: Arc<AtomicBool>,
+    fail_acknowledge: Arc<AtomicBool>,
+}
+
+/// A two-phase processor that records its side effects in a [`Ledger`]
+struct LedgerProcessor {
+    ledger: Ledger,
+}
+
+#[async_trait::async_trait]
+impl Processor for LedgerProcessor {
+    type Sentinel = u64;
+
+    async fn next(
+        &self,
+        current: Option<u64>,
+    ) -> Result<Next<u64>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Next::advance(current.unwrap_or(0) + 1))
+    }
+
+    async fn execute(
+        &self,
+        _current: Option<&u64>,
+        next: &u64,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if self.ledger.fail_execute.load(Ordering::SeqCst) {
+            return Err("crashed while executing".into());
+        }
+
+        self.ledger.executed.lock().unwrap().push(*next);
+        Ok(())
+    }
+
+    async fn acknowledge(
+        &self,
+        next: &u64,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if self.ledger.fail_acknowledge.load(Ordering::SeqCst) {
+            return Err("crashed while acknowledging".into());
+        }
+
+        self.ledger.acknowledged.lock().unwrap().push(*next);
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use std::{
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use mr_prober::{
+    auto::strategy::{AutoProberCfg, AutoProberStrategy},
+    proc::{next::Next, FnProcessor},
+    runtime::{Runtime as _, RuntimeImpl},
+    Prober,
+};
+
+mod common;
+
+#[test]
+fn auto_prober_follows_bounded_retry_hints() {
+    common::block_on(async {
+        // ARRANGE
+        let calls = Arc::new(AtomicU32::new(0));
+        let processor = FnProcessor::from({
+            let calls = Arc::clone(&calls);
+            move |_: Option<u32>| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async { Ok(Next::empty().with_probe_after(Duration::from_secs(3600))) }
+            }
+        });
+
+        // ACT
+        let handle = Prober::in_memory(processor)
+            .into_auto(AutoProberCfg {
+                on_empty: AutoProberStrategy::Delay(Duration::from_secs(60)),
+                ..Default::default()
+            })
+            .with_retry_hint_bounds(Duration::ZERO, Duration::from_millis(50))
+            .spawn();
+        RuntimeImpl::sleep(Duration::from_millis(220)).await;
+        handle.shutdown();
+        handle.await.unwrap();
+
+        // ASSERT
+        // neither the configured delay nor the hint, but the bound in between
+        assert!((4..=6).contains(&calls.load(Ordering::SeqCst)));
+    })
+}
+
+#[test]
+fn auto_prober_ignores_retry_hints_while_there_is_something_new() {
+    common::block_on(async {
+        // ARRANGE
+        let processor = FnProcessor::from(|current: Option<u32>| async move {
+            let next = current.unwrap_or(0) + 1;
+            Ok(if next <= 3 {
+                Next::advance(next).with_probe_after(Duration::from_secs(3600))
+            } else {
+                Next::empty()
+            })
+        });
+
+        // ACT
+        let exit = RuntimeImpl::timeout(
+            Duration::from_secs(1),
+            Prober::in_memory(processor)
+                .into_auto(Default::default())
+                .spawn(),
+        )
+        .await
+        .expect("should keep probing back to back")
+        .unwrap();
+
+        // ASSERT
+        assert_eq!(exit.summary.successes, 3);
+    })
+}
+
+#[test]
+#[should_panic(expected = "retry hint bounds")]
+fn rejects_retry_hint_bounds_the_wrong_way_around() {
+    let processor = FnProcessor::from(|_: Option<u32>| async { Ok(Next::empty()) });
+
+    Prober::in_memory(processor)
+        .into_auto(Default::default())
+        .with_retry_hint_bounds(Duration::from_secs(1), Duration::ZERO);
+}
@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use mr_prober::{
+    auto::exit::ExitReason,
+    proc::{error::ClassifiedError, next::Next, FnProcessor, Processor},
+    runtime::{Runtime as _, RuntimeImpl},
+    Prober,
+};
+
+mod common;
+
+#[test]
+fn auto_prober_stops_on_errors_that_cannot_be_skipped() {
+    common::block_on(async {
+        // ARRANGE
+        let processor = FnProcessor::from(|_: Option<u32>| async {
+            Err::<Option<u32>, _>(ClassifiedError::skip("corrupt record").into())
+        });
+
+        // ACT
+        let exit = RuntimeImpl::timeout(
+            Duration::from_secs(1),
+            Prober::in_memory(processor)
+                .into_auto(Default::default())
+                .spawn(),
+        )
+        .await
+        .expect("should not keep probing the same sentinel")
+        .unwrap();
+
+        // ASSERT
+        assert!(matches!(exit.reason, ExitReason::AbortedOnError(_)));
+        assert_eq!(exit.summary.errors, 1);
+    })
+}
+
+#[test]
+fn auto_prober_counts_skipping_as_committing() {
+    common::block_on(async {
+        // ARRANGE
+        let mut handle = Prober::in_memory(SkipsCorrupt)
+            .into_auto(Default::default())
+            .spawn();
+
+        // ACT
+        let exit = (&mut handle).await.unwrap();
+
+        // ASSERT
+        assert!(matches!(exit.reason, ExitReason::AbortedOnEmpty));
+        assert!(handle.status().last_commit_at.is_some());
+    })
+}
+
+/// Fails on the start and skips it, then has nothing else
+struct SkipsCorrupt;
+
+#[async_trait::async_trait]
+impl Processor for SkipsCorrupt {
+    type Sentinel = u32;
+
+    async fn next(
+        &self,
+        current: Option<u32>,
+    ) -> Result<Next<u32>, Box<dyn std::error::Error + Send + Sync>> {
+        match current {
+            None => Err(ClassifiedError::skip("corrupt record").into()),
+            Some(_) => Ok(Next::empty()),
+        }
+    }
+
+    async fn skip(
+        &self,
+        _current: Option<&u32>,
+    ) -> Result<Option<u32>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Some(1))
+    }
+}